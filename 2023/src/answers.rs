@@ -0,0 +1,222 @@
+use std::{collections::BTreeMap, fmt::Display, fs, io};
+
+use crate::{
+    input::InputKind,
+    solution::{Answer, Part},
+};
+
+pub const ANSWERS_PATH: &str = "./answers.toml";
+
+/// Expected answers, stored in a small subset of TOML. Samples get their own
+/// section and usually only have some of the parts:
+///
+/// ```toml
+/// [day17]
+/// part1 = "1076"
+/// part2 = "1219"
+///
+/// [day17.sample2]
+/// part2 = "71"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    days: BTreeMap<(u8, InputKind), BTreeMap<Part, String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Check::Pass => f.pad("pass"),
+            Check::Fail { .. } => f.pad("FAIL"),
+            Check::Missing => f.pad("missing"),
+        }
+    }
+}
+
+impl Answers {
+    pub fn parse(contents: &str) -> Result<Answers, String> {
+        let mut answers = Answers::default();
+        let mut day = None;
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                day = Some(parse_section(section).ok_or(format!(
+                    "line {}: unknown section [{}]",
+                    i + 1,
+                    section
+                ))?);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(format!("line {}: expected `key = \"value\"`", i + 1))?;
+            let part = match key.trim() {
+                "part1" => Part::One,
+                "part2" => Part::Two,
+                k => return Err(format!("line {}: unknown key {}", i + 1, k)),
+            };
+            let value = value
+                .trim()
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .ok_or(format!("line {}: value should be quoted", i + 1))?;
+            let (day, kind) =
+                day.ok_or(format!("line {}: key outside of a [dayN] section", i + 1))?;
+
+            answers.set(day, kind, part, value.to_string());
+        }
+
+        Ok(answers)
+    }
+
+    /// Loads the answers file, a missing file is the same as an empty one.
+    pub fn load(path: &str) -> Result<Answers, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Answers::parse(&contents).map_err(|e| format!("{}: {}", path, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("{}: {}", path, e)),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, day: u8, kind: InputKind, part: Part) -> Option<&String> {
+        self.days
+            .get(&(day, kind))
+            .and_then(|parts| parts.get(&part))
+    }
+
+    pub fn set(&mut self, day: u8, kind: InputKind, part: Part, answer: String) {
+        self.days
+            .entry((day, kind))
+            .or_default()
+            .insert(part, answer);
+    }
+
+    /// Parts with an expected answer for that input.
+    pub fn parts(&self, day: u8, kind: InputKind) -> Vec<Part> {
+        self.days
+            .get(&(day, kind))
+            .map(|parts| parts.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn check(&self, day: u8, kind: InputKind, part: Part, answer: &Answer) -> Check {
+        match self.get(day, kind, part) {
+            Some(expected) if *expected == answer.to_string() => Check::Pass,
+            Some(expected) => Check::Fail {
+                expected: expected.clone(),
+            },
+            None => Check::Missing,
+        }
+    }
+}
+
+fn parse_section(section: &str) -> Option<(u8, InputKind)> {
+    let section = section.strip_prefix("day")?;
+    match section.split_once(".sample") {
+        Some((day, k)) => Some((day.parse().ok()?, InputKind::Sample(k.parse().ok()?))),
+        None => Some((section.parse().ok()?, InputKind::Real)),
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, ((day, kind), parts)) in self.days.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match kind {
+                InputKind::Real => writeln!(f, "[day{}]", day)?,
+                InputKind::Sample(k) => writeln!(f, "[day{}.sample{}]", day, k)?,
+            }
+            for (part, answer) in parts {
+                writeln!(f, "part{} = \"{}\"", part, answer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        input::InputKind,
+        solution::{Answer, Part},
+    };
+
+    use super::{Answers, Check};
+
+    #[test]
+    fn test_parse_and_print() {
+        let contents = "# comment\n[day17.sample2]\npart2 = \"71\"\n[day2]\npart1 = \"8\"\n\n[day17]\npart1 = \"1076\"\npart2 = \"1219\"\n";
+        let answers = Answers::parse(contents).unwrap();
+
+        assert_eq!(
+            answers.get(17, InputKind::Real, Part::Two),
+            Some(&"1219".to_string())
+        );
+        assert_eq!(
+            answers.get(17, InputKind::Sample(2), Part::Two),
+            Some(&"71".to_string())
+        );
+        assert_eq!(answers.get(2, InputKind::Real, Part::Two), None);
+        assert_eq!(answers.parts(17, InputKind::Sample(2)), vec![Part::Two]);
+
+        let printed = answers.to_string();
+        assert_eq!(
+            printed,
+            "[day2]\npart1 = \"8\"\n\n[day17]\npart1 = \"1076\"\npart2 = \"1219\"\n\n[day17.sample2]\npart2 = \"71\"\n"
+        );
+        assert_eq!(Answers::parse(&printed).unwrap(), answers);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Answers::parse("part1 = \"1\"").is_err());
+        assert!(Answers::parse("[day1]\npart3 = \"1\"").is_err());
+        assert!(Answers::parse("[day1]\npart1 = 1").is_err());
+        assert!(Answers::parse("[days]").is_err());
+        assert!(Answers::parse("[day1.example]").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse("[day1]\npart1 = \"42\"").unwrap();
+        let real = InputKind::Real;
+
+        assert_eq!(
+            answers.check(1, real, Part::One, &Answer::from(42)),
+            Check::Pass
+        );
+        assert_eq!(
+            answers.check(1, real, Part::One, &Answer::from(41)),
+            Check::Fail {
+                expected: "42".to_string()
+            }
+        );
+        assert_eq!(
+            answers.check(1, real, Part::Two, &Answer::from(1)),
+            Check::Missing
+        );
+        assert_eq!(
+            answers.check(1, InputKind::Sample(1), Part::One, &Answer::from(42)),
+            Check::Missing
+        );
+    }
+}
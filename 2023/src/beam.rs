@@ -0,0 +1,308 @@
+//! Beams of light going through a grid of mirrors and splitters, from day 16.
+//! `.` lets a beam through, `/` and `\` turn it, and `|` and `-` split a beam
+//! that hits their flat side in two.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use crate::{
+    grid::Grid,
+    point::{Direction, Point},
+    search,
+};
+
+/// Where a beam going `dir` leaves a cell with `tile` towards.
+pub fn deflect(tile: char, dir: Direction) -> Vec<Direction> {
+    let vertical = dir.is_vertical();
+    match tile {
+        '|' if !vertical => vec![Direction::Up, Direction::Down],
+        '-' if vertical => vec![Direction::Left, Direction::Right],
+        '\\' if vertical => vec![dir.turn_left()],
+        '\\' => vec![dir.turn_right()],
+        '/' if vertical => vec![dir.turn_right()],
+        '/' => vec![dir.turn_left()],
+        _ => vec![dir],
+    }
+}
+
+/// Which cells a beam energises, for any cell and direction it comes in from.
+///
+/// Beams only change direction on the tiles that aren't `.`, so the states
+/// that matter are a beam reaching one of those tiles going some way. Each of
+/// them leads, through straight runs of empty cells, to a few others. Beams
+/// can go round in loops, so the states are grouped into the loops they're
+/// part of first. The groups can't loop between them, so the cells energised
+/// from a group are its own plus the ones energised from the groups it leads
+/// to, which are all worked out once and shared by every entry.
+#[derive(Debug, Clone)]
+pub struct BeamTracer {
+    grid: Grid<char>,
+    states: Vec<(Point, Direction)>,
+    index: HashMap<(Point, Direction), usize>,
+    /// The runs out of each state.
+    runs: Vec<Vec<Run>>,
+    /// The group of each state, and the cells energised from each group.
+    group: Vec<usize>,
+    energised: Vec<Cells>,
+}
+
+/// The empty cells a beam crosses in a straight line after leaving a tile,
+/// and the state at the end of it, if it doesn't leave the grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Run {
+    cells: Vec<Point>,
+    to: Option<usize>,
+}
+
+impl BeamTracer {
+    pub fn new(grid: &Grid<char>) -> Self {
+        let states = grid
+            .points()
+            .filter(|&p| grid[p] != '.')
+            .flat_map(|p| Direction::ALL.map(|dir| (p, dir)))
+            .collect::<Vec<_>>();
+        let index = states.iter().enumerate().map(|(i, s)| (*s, i)).collect();
+
+        let mut tracer = Self {
+            grid: grid.clone(),
+            states,
+            index,
+            runs: Vec::new(),
+            group: Vec::new(),
+            energised: Vec::new(),
+        };
+
+        tracer.runs = tracer
+            .states
+            .iter()
+            .map(|&(p, dir)| {
+                deflect(grid[p], dir)
+                    .into_iter()
+                    .filter_map(|out| grid.step(p, out).map(|next| tracer.run(next, out)))
+                    .collect()
+            })
+            .collect();
+        tracer.group_states();
+        tracer
+    }
+
+    /// Follows a beam from `start` across empty cells.
+    fn run(&self, start: Point, dir: Direction) -> Run {
+        let mut cells = Vec::new();
+        let mut p = start;
+        loop {
+            if let Some(&to) = self.index.get(&(p, dir)) {
+                return Run {
+                    cells,
+                    to: Some(to),
+                };
+            }
+            cells.push(p);
+            match self.grid.step(p, dir) {
+                Some(next) => p = next,
+                None => return Run { cells, to: None },
+            }
+        }
+    }
+
+    fn group_states(&mut self) {
+        let all = (0..self.states.len()).collect::<Vec<_>>();
+        let groups = search::strongly_connected_components(&all, |&state| {
+            self.runs[state]
+                .iter()
+                .filter_map(|run| run.to)
+                .collect::<Vec<_>>()
+        });
+
+        let mut group = vec![0; self.states.len()];
+        for (g, states) in groups.iter().enumerate() {
+            for &state in states {
+                group[state] = g;
+            }
+        }
+
+        // A group comes after the ones it leads to, so those are done already
+        let cells = (self.grid.rows() * self.grid.cols()) as usize;
+        let mut energised: Vec<Cells> = Vec::with_capacity(groups.len());
+
+        for (g, states) in groups.iter().enumerate() {
+            let mut lit = Cells::new(cells);
+            for &state in states {
+                lit.insert(self.cell(self.states[state].0));
+                for run in &self.runs[state] {
+                    for &p in &run.cells {
+                        lit.insert(self.cell(p));
+                    }
+                    if let Some(to) = run.to.filter(|&to| group[to] != g) {
+                        lit.union_with(&energised[group[to]]);
+                    }
+                }
+            }
+            energised.push(lit);
+        }
+
+        self.group = group;
+        self.energised = energised;
+    }
+
+    fn cell(&self, p: Point) -> usize {
+        (p.y * self.grid.cols() + p.x) as usize
+    }
+
+    /// Number of cells energised by a beam coming into `start` going `dir`.
+    pub fn energised(&self, start: Point, dir: Direction) -> usize {
+        if !self.grid.contains_point(start) {
+            return 0;
+        }
+        let run = self.run(start, dir);
+        match run.to {
+            Some(to) => {
+                let mut lit = self.energised[self.group[to]].clone();
+                for p in run.cells {
+                    lit.insert(self.cell(p));
+                }
+                lit.len()
+            }
+            None => run.cells.len(),
+        }
+    }
+
+    /// Every way into the grid from outside, along the edges.
+    pub fn edge_entries(&self) -> Vec<(Point, Direction)> {
+        let (last_col, last_row) = (self.grid.cols() - 1, self.grid.rows() - 1);
+        let mut entries = Vec::new();
+        for x in 0..=last_col {
+            entries.push((Point::new(x, 0), Direction::Down));
+            entries.push((Point::new(x, last_row), Direction::Up));
+        }
+        for y in 0..=last_row {
+            entries.push((Point::new(0, y), Direction::Right));
+            entries.push((Point::new(last_col, y), Direction::Left));
+        }
+        entries
+    }
+
+    /// Cells energised from each of the `edge_entries`.
+    pub fn edge_energised(&self) -> Vec<((Point, Direction), usize)> {
+        self.edge_entries()
+            .into_iter()
+            .map(|(p, dir)| ((p, dir), self.energised(p, dir)))
+            .collect()
+    }
+}
+
+/// A set of cells by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cells {
+    bits: Vec<u64>,
+}
+
+impl Cells {
+    fn new(size: usize) -> Self {
+        Self {
+            bits: vec![0; size.div_ceil(64)],
+        }
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.bits[i / 64] |= 1 << (i % 64);
+    }
+
+    fn union_with(&mut self, other: &Self) {
+        for (a, b) in self.bits.iter_mut().zip(&other.bits) {
+            *a |= b;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.bits.iter().map(|b| b.count_ones() as usize).sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::{
+        grid::Grid,
+        point::{Direction, Point},
+    };
+
+    use super::{deflect, BeamTracer};
+
+    const CONTRAPTION: &str = r"
+.|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    /// Follows every beam one cell at a time.
+    fn simulate(grid: &Grid<char>, start: Point, dir: Direction) -> usize {
+        let mut seen = HashSet::new();
+        let mut beams = vec![(start, dir)];
+        while let Some((p, dir)) = beams.pop() {
+            if !grid.contains_point(p) || !seen.insert((p, dir)) {
+                continue;
+            }
+            for out in deflect(grid[p], dir) {
+                beams.push((p.step(out), out));
+            }
+        }
+        seen.iter().map(|(p, _)| p).collect::<HashSet<_>>().len()
+    }
+
+    #[test]
+    fn test_deflect() {
+        assert_eq!(deflect('.', Direction::Up), [Direction::Up]);
+        assert_eq!(deflect('|', Direction::Down), [Direction::Down]);
+        assert_eq!(
+            deflect('-', Direction::Down),
+            [Direction::Left, Direction::Right]
+        );
+        assert_eq!(deflect('/', Direction::Right), [Direction::Up]);
+        assert_eq!(deflect('/', Direction::Down), [Direction::Left]);
+        assert_eq!(deflect('\\', Direction::Right), [Direction::Down]);
+        assert_eq!(deflect('\\', Direction::Up), [Direction::Left]);
+    }
+
+    #[test]
+    fn test_example() {
+        let grid: Grid<char> = CONTRAPTION.trim().parse().unwrap();
+        let tracer = BeamTracer::new(&grid);
+
+        assert_eq!(tracer.energised(Point::ORIGIN, Direction::Right), 46);
+        assert_eq!(tracer.energised(Point::new(3, 0), Direction::Down), 51);
+        assert_eq!(tracer.energised(Point::new(-1, 0), Direction::Right), 0);
+
+        let all = tracer.edge_energised();
+        assert_eq!(all.len(), 40);
+        assert_eq!(all.iter().map(|(_, n)| *n).max(), Some(51));
+        for ((p, dir), n) in all {
+            assert_eq!(n, simulate(&grid, p, dir), "{} {:?}", p, dir);
+        }
+    }
+
+    #[test]
+    fn test_loops() {
+        // The beam goes round the mirrors forever, and splits on the way in
+        let grid: Grid<char> = "\
+./..\\
+.....
+.|...
+.\\../
+....."
+            .parse()
+            .unwrap();
+        let tracer = BeamTracer::new(&grid);
+        for ((p, dir), n) in tracer.edge_energised() {
+            assert_eq!(n, simulate(&grid, p, dir), "{} {:?}", p, dir);
+        }
+    }
+}
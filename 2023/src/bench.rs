@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use crate::{
+    input::InputError,
+    solution::{Day, Part},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort();
+        Some(Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+/// Runs the day `runs` times and collects the timings of the parse step and of
+/// every part, in that order.
+pub fn bench(
+    day: &Day,
+    contents: &str,
+    parts: &[Part],
+    runs: usize,
+) -> Result<Vec<(String, Stats)>, InputError> {
+    let mut parse_times = Vec::new();
+    let mut part_times = vec![Vec::new(); parts.len()];
+
+    for _ in 0..runs {
+        let run = day.run(contents, parts)?;
+        parse_times.push(run.parse_time);
+        for (i, (_, _, elapsed)) in run.answers.iter().enumerate() {
+            part_times[i].push(*elapsed);
+        }
+    }
+
+    let mut res = Vec::new();
+    if let Some(stats) = Stats::from_samples(parse_times) {
+        res.push((String::from("parse"), stats));
+    }
+    for (part, times) in parts.iter().zip(part_times) {
+        if let Some(stats) = Stats::from_samples(times) {
+            res.push((part.to_string(), stats));
+        }
+    }
+    Ok(res)
+}
+
+pub fn format_duration(d: Duration) -> String {
+    format!("{:.2?}", d)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Stats;
+
+    #[test]
+    fn test_stats() {
+        let samples = [5, 1, 3, 2, 4].map(Duration::from_millis).to_vec();
+        let stats = Stats::from_samples(samples).unwrap();
+
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.max, Duration::from_millis(5));
+
+        assert_eq!(Stats::from_samples(Vec::new()), None);
+    }
+}
@@ -0,0 +1,277 @@
+//! Bricks falling into a stack until they rest on the ground or on each other,
+//! like the sand bricks of day 22.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use crate::{
+    input::InputError,
+    parsing::{coords, lines, pair},
+};
+
+/// A straight line of cubes from `start` to `end`, both included, with
+/// `start` the smaller corner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Brick {
+    pub start: [i32; 3],
+    pub end: [i32; 3],
+}
+
+impl Brick {
+    pub fn new(a: [i32; 3], b: [i32; 3]) -> Self {
+        Self {
+            start: [a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2])],
+            end: [a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2])],
+        }
+    }
+
+    /// `1,0,1~1,2,1`
+    pub fn parse(s: &str) -> Result<Self, InputError> {
+        let (a, b) = pair(s, "~")?;
+        Ok(Self::new(coords(a)?, coords(b)?))
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.start[2]
+    }
+
+    pub fn top(&self) -> i32 {
+        self.end[2]
+    }
+
+    pub fn height(&self) -> i32 {
+        self.end[2] - self.start[2] + 1
+    }
+
+    /// The (x, y) cells the brick covers, seen from above.
+    pub fn footprint(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (start, end) = (self.start, self.end);
+        (start[0]..=end[0]).flat_map(move |x| (start[1]..=end[1]).map(move |y| (x, y)))
+    }
+
+    /// Whether one brick would land on the other if it fell far enough.
+    pub fn footprint_overlaps(&self, other: &Self) -> bool {
+        (0..2).all(|d| self.start[d] <= other.end[d] && other.start[d] <= self.end[d])
+    }
+
+    /// The same brick moved down so its bottom is at `z`.
+    fn dropped_to(&self, z: i32) -> Self {
+        let dz = self.start[2] - z;
+        Self {
+            start: [self.start[0], self.start[1], z],
+            end: [self.end[0], self.end[1], self.end[2] - dz],
+        }
+    }
+}
+
+/// Bricks after they all fell as far as they could, and which ones hold up
+/// which. Bricks are ordered from the lowest, before falling, so a brick
+/// always comes after the ones it rests on.
+#[derive(Clone, Debug)]
+pub struct BrickStack {
+    bricks: Vec<Brick>,
+    below: Vec<Vec<usize>>,
+    above: Vec<Vec<usize>>,
+}
+
+impl BrickStack {
+    /// One brick per line.
+    pub fn parse(contents: &str) -> Result<Self, InputError> {
+        Ok(Self::settle(lines(contents, Brick::parse)?))
+    }
+
+    /// Lets every brick fall. The bricks are dropped from the lowest one up,
+    /// keeping the highest brick under each (x, y) cell.
+    pub fn settle(mut bricks: Vec<Brick>) -> Self {
+        bricks.sort_by_key(|b| b.bottom());
+
+        let mut heights: HashMap<(i32, i32), (i32, usize)> = HashMap::new();
+        let mut settled = Vec::with_capacity(bricks.len());
+        let mut below = vec![Vec::new(); bricks.len()];
+        let mut above = vec![Vec::new(); bricks.len()];
+
+        for (i, brick) in bricks.iter().enumerate() {
+            let under = brick
+                .footprint()
+                .filter_map(|cell| heights.get(&cell))
+                .copied()
+                .collect::<Vec<_>>();
+            let rest_on = under.iter().map(|(top, _)| *top).max().unwrap_or(0);
+
+            for &(top, j) in &under {
+                if top == rest_on && !below[i].contains(&j) {
+                    below[i].push(j);
+                    above[j].push(i);
+                }
+            }
+
+            let brick = brick.dropped_to(rest_on + 1);
+            for cell in brick.footprint() {
+                heights.insert(cell, (brick.top(), i));
+            }
+            settled.push(brick);
+        }
+
+        Self {
+            bricks: settled,
+            below,
+            above,
+        }
+    }
+
+    pub fn bricks(&self) -> &[Brick] {
+        &self.bricks
+    }
+
+    pub fn len(&self) -> usize {
+        self.bricks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bricks.is_empty()
+    }
+
+    /// The bricks `i` rests on, none for the ones on the ground.
+    pub fn supported_by(&self, i: usize) -> &[usize] {
+        &self.below[i]
+    }
+
+    /// The bricks resting on `i`.
+    pub fn supporting(&self, i: usize) -> &[usize] {
+        &self.above[i]
+    }
+
+    /// Whether `i` can be taken out without any other brick falling.
+    pub fn is_safe_to_remove(&self, i: usize) -> bool {
+        self.above[i].iter().all(|&j| self.below[j].len() > 1)
+    }
+
+    /// How many other bricks fall if each brick is taken out, chain reactions
+    /// included. A brick falls when `i` is taken out if every way down from it
+    /// to the ground goes through `i`, so the counts are the sizes of the
+    /// dominator tree of the support graph, rooted at the ground.
+    pub fn falling_counts(&self) -> Vec<usize> {
+        let ground = self.len();
+        let mut idom = vec![ground; self.len() + 1];
+        let mut depth = vec![0; self.len() + 1];
+
+        // Supporters always come first, so their dominators are already known
+        for i in 0..self.len() {
+            let dom = self.below[i]
+                .iter()
+                .copied()
+                .reduce(|a, b| common_dominator(&idom, &depth, a, b))
+                .unwrap_or(ground);
+            idom[i] = dom;
+            depth[i] = depth[dom] + 1;
+        }
+
+        let mut sizes = vec![1; self.len() + 1];
+        for i in (0..self.len()).rev() {
+            sizes[idom[i]] += sizes[i];
+        }
+        sizes.truncate(self.len());
+        sizes.iter().map(|s| s - 1).collect()
+    }
+}
+
+/// The closest node that dominates both `a` and `b`.
+fn common_dominator(idom: &[usize], depth: &[usize], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        if depth[a] >= depth[b] {
+            a = idom[a];
+        } else {
+            b = idom[b];
+        }
+    }
+    a
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Brick, BrickStack};
+
+    const EXAMPLE: &str = "\
+1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+
+    #[test]
+    fn test_brick() {
+        let b = Brick::parse("0,0,10~0,0,1").unwrap();
+        assert_eq!(b.start, [0, 0, 1]);
+        assert_eq!(b.height(), 10);
+        assert_eq!(b.footprint().count(), 1);
+
+        let wide = Brick::parse("0,0,2~2,0,2").unwrap();
+        assert_eq!(wide.footprint().count(), 3);
+        assert!(wide.footprint_overlaps(&b));
+        assert!(!wide.footprint_overlaps(&Brick::parse("0,1,1~0,1,1").unwrap()));
+    }
+
+    #[test]
+    fn test_example() {
+        let stack = BrickStack::parse(EXAMPLE).unwrap();
+
+        let bottoms = stack
+            .bricks()
+            .iter()
+            .map(|b| b.bottom())
+            .collect::<Vec<_>>();
+        assert_eq!(bottoms, [1, 2, 2, 3, 3, 4, 5]);
+        assert_eq!(stack.supported_by(0), []);
+        assert_eq!(stack.supporting(0), [1, 2]);
+        assert_eq!(stack.supported_by(3), [1, 2]);
+
+        let safe = (0..stack.len()).filter(|&i| stack.is_safe_to_remove(i));
+        assert_eq!(safe.count(), 5);
+        assert_eq!(stack.falling_counts(), [6, 0, 0, 0, 0, 1, 0]);
+    }
+
+    /// Takes out brick `i` and lets the bricks above fall one by one, a brick
+    /// falling once everything it rested on has.
+    fn fall_without(stack: &BrickStack, i: usize) -> usize {
+        let mut fallen = vec![false; stack.len()];
+        fallen[i] = true;
+        for j in i + 1..stack.len() {
+            let below = stack.supported_by(j);
+            fallen[j] = !below.is_empty() && below.iter().all(|&k| fallen[k]);
+        }
+        fallen.iter().filter(|&&f| f).count() - 1
+    }
+
+    #[test]
+    fn test_falling_counts() {
+        // Small bricks dropped in a narrow column so they pile up in chains,
+        // spaced out so none of them start inside another
+        let mut seed: u32 = 7;
+        let mut next = |n: u32| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            ((seed >> 16) % n) as i32
+        };
+        let bricks = (0..60)
+            .map(|i| {
+                let z = i * 3;
+                let (x, y) = (next(3), next(3));
+                let len = next(3);
+                match next(3) {
+                    0 => Brick::new([x, y, z + 1], [x + len, y, z + 1]),
+                    1 => Brick::new([x, y, z + 1], [x, y + len, z + 1]),
+                    _ => Brick::new([x, y, z + 1], [x, y, z + 1 + len]),
+                }
+            })
+            .collect::<Vec<_>>();
+        let stack = BrickStack::settle(bricks);
+
+        let counts = stack.falling_counts();
+        for (i, count) in counts.iter().enumerate() {
+            assert_eq!(*count, fall_without(&stack, i), "brick {}", i);
+        }
+        assert!(counts.iter().any(|c| *c > 1));
+    }
+}
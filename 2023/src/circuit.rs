@@ -0,0 +1,437 @@
+//! Modules wired together that pass high and low pulses around every time the
+//! button is pressed, from day 20.
+
+#![allow(dead_code)]
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+};
+
+use crate::{
+    dot::Dot,
+    input::InputError,
+    parsing::{lines, pair},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pulse {
+    Low,
+    High,
+}
+
+impl Display for Pulse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pulse::Low => write!(f, "low"),
+            Pulse::High => write!(f, "high"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// `%`: flips on a low pulse and sends high when it turns on, low when it
+    /// turns off. Ignores high pulses.
+    FlipFlop,
+    /// `&`: remembers the last pulse from each input, sends low if they were
+    /// all high and high otherwise.
+    Conjunction,
+    /// Sends what it gets to all of its targets.
+    Broadcaster,
+    /// Sends a low pulse to the broadcaster when pressed.
+    Button,
+    /// Only named as a target, like `rx`. Receives pulses and does nothing.
+    Output,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Module {
+    pub name: String,
+    pub kind: Kind,
+    pub targets: Vec<usize>,
+}
+
+/// A pulse sent from one module to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Event {
+    pub from: usize,
+    pub to: usize,
+    pub pulse: Pulse,
+}
+
+/// A pulse to look out for: `pulse` arriving at `to`, from `from` or from any
+/// of its inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Watch {
+    pub from: Option<usize>,
+    pub to: usize,
+    pub pulse: Pulse,
+}
+
+impl Watch {
+    pub fn new(to: usize, pulse: Pulse) -> Self {
+        Self {
+            from: None,
+            to,
+            pulse,
+        }
+    }
+
+    pub fn from(self, from: usize) -> Self {
+        Self {
+            from: Some(from),
+            ..self
+        }
+    }
+
+    pub fn matches(&self, event: &Event) -> bool {
+        event.to == self.to
+            && event.pulse == self.pulse
+            && self.from.is_none_or(|from| from == event.from)
+    }
+}
+
+/// The modules and the state they're in. Modules are referred to by index,
+/// `index_of` finds them by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Circuit {
+    modules: Vec<Module>,
+    index: HashMap<String, usize>,
+    inputs: Vec<Vec<usize>>,
+    /// Whether each flip-flop is on.
+    on: Vec<bool>,
+    /// The last pulse from each input of each conjunction, in `inputs` order.
+    memory: Vec<Vec<Pulse>>,
+    presses: u64,
+}
+
+impl Circuit {
+    pub const BUTTON: &'static str = "button";
+    pub const BROADCASTER: &'static str = "broadcaster";
+
+    /// `%a -> inv, con`, one module per line. Names that are only targets
+    /// become `Output` modules, and a button wired to the broadcaster is added.
+    pub fn parse(contents: &str) -> Result<Self, InputError> {
+        let specs = lines(contents, |l| {
+            let (module, targets) = pair(l, " -> ")?;
+            let (kind, name) = match module.as_bytes().first() {
+                Some(b'%') => (Kind::FlipFlop, &module[1..]),
+                Some(b'&') => (Kind::Conjunction, &module[1..]),
+                _ if module == Self::BROADCASTER => (Kind::Broadcaster, module),
+                _ => return Err(InputError::new(format!("unknown module `{}`", module))),
+            };
+            Ok((kind, name, targets.split(", ").collect::<Vec<_>>()))
+        })?;
+
+        let mut circuit = Self {
+            modules: Vec::new(),
+            index: HashMap::new(),
+            inputs: Vec::new(),
+            on: Vec::new(),
+            memory: Vec::new(),
+            presses: 0,
+        };
+
+        let button = circuit.add_module(Self::BUTTON, Kind::Button);
+        for (kind, name, _) in &specs {
+            circuit.add_module(name, *kind);
+        }
+        let broadcaster = circuit
+            .index_of(Self::BROADCASTER)
+            .ok_or(InputError::new("no broadcaster"))?;
+        circuit.connect(button, broadcaster);
+
+        for (_, name, targets) in &specs {
+            let from = circuit.index[*name];
+            for target in targets {
+                let to = match circuit.index_of(target) {
+                    Some(to) => to,
+                    None => circuit.add_module(target, Kind::Output),
+                };
+                circuit.connect(from, to);
+            }
+        }
+
+        circuit.reset();
+        Ok(circuit)
+    }
+
+    fn add_module(&mut self, name: &str, kind: Kind) -> usize {
+        self.modules.push(Module {
+            name: name.to_string(),
+            kind,
+            targets: Vec::new(),
+        });
+        self.inputs.push(Vec::new());
+        self.index.insert(name.to_string(), self.modules.len() - 1);
+        self.modules.len() - 1
+    }
+
+    fn connect(&mut self, from: usize, to: usize) {
+        self.modules[from].targets.push(to);
+        self.inputs[to].push(from);
+    }
+
+    /// Turns every flip-flop off and makes every conjunction remember low
+    /// pulses, as before the first press.
+    pub fn reset(&mut self) {
+        self.on = vec![false; self.len()];
+        self.memory = self
+            .inputs
+            .iter()
+            .map(|inputs| vec![Pulse::Low; inputs.len()])
+            .collect();
+        self.presses = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    pub fn modules(&self) -> &[Module] {
+        &self.modules
+    }
+
+    pub fn module(&self, i: usize) -> &Module {
+        &self.modules[i]
+    }
+
+    pub fn name(&self, i: usize) -> &str {
+        &self.modules[i].name
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    /// The modules that send pulses to `i`.
+    pub fn inputs(&self, i: usize) -> &[usize] {
+        &self.inputs[i]
+    }
+
+    /// Button presses since the start.
+    pub fn presses(&self) -> u64 {
+        self.presses
+    }
+
+    /// Whether every module is back to how it was before the first press.
+    pub fn is_reset(&self) -> bool {
+        !self.on.contains(&true) && self.memory.iter().flatten().all(|&p| p == Pulse::Low)
+    }
+
+    /// Presses the button once, returning every pulse sent in the order they
+    /// were sent, starting with the button's.
+    pub fn press(&mut self) -> Vec<Event> {
+        self.presses += 1;
+
+        let button = self.index[Self::BUTTON];
+        let mut log = Vec::new();
+        let mut queue = VecDeque::from([Event {
+            from: button,
+            to: self.modules[button].targets[0],
+            pulse: Pulse::Low,
+        }]);
+
+        while let Some(event) = queue.pop_front() {
+            log.push(event);
+            if let Some(pulse) = self.receive(event) {
+                queue.extend(self.modules[event.to].targets.iter().map(|&to| Event {
+                    from: event.to,
+                    to,
+                    pulse,
+                }));
+            }
+        }
+        log
+    }
+
+    /// Updates the receiving module, returning what it sends to its targets.
+    fn receive(&mut self, event: Event) -> Option<Pulse> {
+        let to = event.to;
+        match self.modules[to].kind {
+            Kind::FlipFlop => match event.pulse {
+                Pulse::High => None,
+                Pulse::Low => {
+                    self.on[to] = !self.on[to];
+                    Some(if self.on[to] { Pulse::High } else { Pulse::Low })
+                }
+            },
+            Kind::Conjunction => {
+                let slot = self.inputs[to].iter().position(|&i| i == event.from)?;
+                self.memory[to][slot] = event.pulse;
+                if self.memory[to].iter().all(|&p| p == Pulse::High) {
+                    Some(Pulse::Low)
+                } else {
+                    Some(Pulse::High)
+                }
+            }
+            Kind::Broadcaster | Kind::Button => Some(event.pulse),
+            Kind::Output => None,
+        }
+    }
+
+    /// Keeps pressing the button until a pulse matching `watch` is sent, at
+    /// most `limit` times. The press it happened in, counting from the start.
+    pub fn press_until(&mut self, watch: &Watch, limit: u64) -> Option<u64> {
+        for _ in 0..limit {
+            if self.press().iter().any(|e| watch.matches(e)) {
+                return Some(self.presses);
+            }
+        }
+        None
+    }
+
+    /// The conjunction that is the only input of `target`, as the one feeding
+    /// `rx` is.
+    pub fn feeder(&self, target: usize) -> Option<usize> {
+        match self.inputs[target][..] {
+            [i] if self.modules[i].kind == Kind::Conjunction => Some(i),
+            _ => None,
+        }
+    }
+
+    /// `button -low-> broadcaster`, the way the puzzle writes pulses.
+    pub fn trace(&self, event: &Event) -> String {
+        format!(
+            "{} -{}-> {}",
+            self.name(event.from),
+            event.pulse,
+            self.name(event.to)
+        )
+    }
+
+    /// The wiring as a graph, with a shape for each kind of module. Counters
+    /// built out of flip-flops show up as chains feeding a conjunction.
+    pub fn to_dot(&self) -> Dot {
+        let mut dot = Dot::digraph("circuit");
+        for module in &self.modules {
+            let (shape, color) = match module.kind {
+                Kind::FlipFlop => ("box", "lightblue"),
+                Kind::Conjunction => ("invtriangle", "orange"),
+                Kind::Broadcaster => ("doublecircle", "lightgreen"),
+                Kind::Button => ("circle", "lightgreen"),
+                Kind::Output => ("doubleoctagon", "pink"),
+            };
+            dot.node(
+                &module.name,
+                &[("shape", shape), ("style", "filled"), ("fillcolor", color)],
+            );
+        }
+        for module in &self.modules {
+            for &to in &module.targets {
+                dot.edge(&module.name, self.name(to), &[]);
+            }
+        }
+        dot
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Circuit, Kind, Pulse, Watch};
+
+    const LOOP: &str = "\
+broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a";
+
+    const OUTPUT: &str = "\
+broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output";
+
+    #[test]
+    fn test_parse() {
+        let circuit = Circuit::parse(OUTPUT).unwrap();
+        // The button and output are added
+        assert_eq!(circuit.len(), 7);
+
+        let output = circuit.index_of("output").unwrap();
+        assert_eq!(circuit.module(output).kind, Kind::Output);
+        let con = circuit.feeder(output).unwrap();
+        assert_eq!(circuit.name(con), "con");
+        let inputs = circuit.inputs(con).iter().map(|&i| circuit.name(i));
+        assert_eq!(inputs.collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(circuit.feeder(con), None);
+
+        assert!(Circuit::parse("a -> b").is_err());
+    }
+
+    #[test]
+    fn test_press() {
+        let mut circuit = Circuit::parse(LOOP).unwrap();
+        let log = circuit.press();
+        let trace = log.iter().map(|e| circuit.trace(e)).collect::<Vec<_>>();
+        assert_eq!(
+            trace,
+            [
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "broadcaster -low-> b",
+                "broadcaster -low-> c",
+                "a -high-> b",
+                "b -high-> c",
+                "c -high-> inv",
+                "inv -low-> a",
+                "a -low-> b",
+                "b -low-> c",
+                "c -low-> inv",
+                "inv -high-> a",
+            ]
+        );
+        assert!(circuit.is_reset());
+
+        let mut circuit = Circuit::parse(OUTPUT).unwrap();
+        let lows = (0..4).map(|_| {
+            let log = circuit.press();
+            log.iter().filter(|e| e.pulse == Pulse::Low).count()
+        });
+        assert_eq!(lows.collect::<Vec<_>>(), [4, 4, 5, 4]);
+        assert!(circuit.is_reset());
+    }
+
+    #[test]
+    fn test_watch() {
+        let mut circuit = Circuit::parse(OUTPUT).unwrap();
+        let con = circuit.index_of("con").unwrap();
+        let b = circuit.index_of("b").unwrap();
+
+        let watch = Watch::new(con, Pulse::High).from(b);
+        assert_eq!(circuit.press_until(&watch, 10), Some(1));
+        assert_eq!(circuit.press_until(&watch, 10), Some(5));
+
+        let output = circuit.index_of("output").unwrap();
+        circuit.reset();
+        assert_eq!(
+            circuit.press_until(&Watch::new(output, Pulse::Low), 10),
+            Some(1)
+        );
+
+        // The broadcaster only ever gets the button's low pulse
+        let broadcaster = circuit.index_of(Circuit::BROADCASTER).unwrap();
+        circuit.reset();
+        let never = Watch::new(broadcaster, Pulse::High);
+        assert_eq!(circuit.press_until(&never, 100), None);
+        assert_eq!(circuit.presses(), 100);
+    }
+
+    #[test]
+    fn test_dot() {
+        let dot = Circuit::parse(OUTPUT).unwrap().to_dot().to_string();
+        assert!(dot.starts_with("digraph \"circuit\" {"));
+        assert!(dot.contains("\"con\" [shape = \"invtriangle\""));
+        assert!(dot.contains("\"output\" [shape = \"doubleoctagon\""));
+        assert!(dot.contains("\"button\" -> \"broadcaster\";"));
+        assert!(dot.contains("\"a\" -> \"con\";"));
+        assert_eq!(dot.matches(" -> ").count(), 7);
+    }
+}
@@ -0,0 +1,168 @@
+//! Finding where a simulation starts repeating itself, to skip ahead to the
+//! state after a huge number of steps.
+
+#![allow(dead_code)]
+
+use std::{collections::HashMap, hash::Hash};
+
+/// The states after `start` steps and after `start + len` steps are the same,
+/// and `len` is the shortest distance for which that happens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// The fewest steps that end in the same state as `n` steps.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+}
+
+/// Brent's algorithm, only keeping two states around at a time.
+pub fn brent<S: Clone + Eq>(start: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(start);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = step(&hare);
+        len += 1;
+    }
+
+    // With the hare `len` steps ahead, both meet where the cycle starts
+    let mut tortoise = start.clone();
+    let mut hare = after(start, &mut step, len);
+    let mut mu = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+
+    Cycle { start: mu, len }
+}
+
+/// Floyd's tortoise and hare, with the hare going twice as fast.
+pub fn floyd<S: Clone + Eq>(start: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(start);
+    let mut hare = after(start, &mut step, 2);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = after(&hare, &mut step, 2);
+    }
+
+    let mut mu = 0;
+    tortoise = start.clone();
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+
+    let mut len = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        len += 1;
+    }
+
+    Cycle { start: mu, len }
+}
+
+/// Remembers every state, so each step is only computed once.
+pub fn hashed<S: Clone + Eq + Hash>(start: &S, step: impl FnMut(&S) -> S) -> Cycle {
+    history(start, step, None).0.unwrap()
+}
+
+/// The state after `n` steps, skipping over the cycle once it's found.
+pub fn nth_state<S: Clone + Eq + Hash>(start: &S, step: impl FnMut(&S) -> S, n: usize) -> S {
+    let (cycle, mut states) = history(start, step, Some(n));
+    let n = cycle.map_or(n, |cycle| cycle.reduce(n));
+    states.swap_remove(n)
+}
+
+/// The state after `n` steps, computing every one of them.
+pub fn after<S: Clone>(start: &S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let mut state = start.clone();
+    for _ in 0..n {
+        state = step(&state);
+    }
+    state
+}
+
+/// Every state until the first repeated one, or until the one after `limit`
+/// steps if that comes first.
+fn history<S: Clone + Eq + Hash>(
+    start: &S,
+    mut step: impl FnMut(&S) -> S,
+    limit: Option<usize>,
+) -> (Option<Cycle>, Vec<S>) {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = start.clone();
+
+    loop {
+        if let Some(&first) = seen.get(&state) {
+            let cycle = Cycle {
+                start: first,
+                len: states.len() - first,
+            };
+            return (Some(cycle), states);
+        }
+        seen.insert(state.clone(), states.len());
+        states.push(state.clone());
+
+        if limit == Some(states.len() - 1) {
+            return (None, states);
+        }
+        state = step(&state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{after, brent, floyd, hashed, nth_state, Cycle};
+
+    /// 0, 1, 2, 3, 4, 5, 6, 3, 4, 5, 6, 3...
+    fn step(n: &usize) -> usize {
+        if *n < 6 {
+            n + 1
+        } else {
+            3
+        }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let expected = Cycle { start: 3, len: 4 };
+        assert_eq!(brent(&0, step), expected);
+        assert_eq!(floyd(&0, step), expected);
+        assert_eq!(hashed(&0, step), expected);
+
+        assert_eq!(hashed(&5, step), Cycle { start: 0, len: 4 });
+        assert_eq!(brent(&5, step), Cycle { start: 0, len: 4 });
+        assert_eq!(floyd(&5, step), Cycle { start: 0, len: 4 });
+    }
+
+    #[test]
+    fn test_nth_state() {
+        assert_eq!(Cycle { start: 3, len: 4 }.reduce(2), 2);
+        assert_eq!(Cycle { start: 3, len: 4 }.reduce(1_000_000_001), 5);
+
+        for n in 0..20 {
+            assert_eq!(nth_state(&0, step, n), after(&0, step, n));
+        }
+        assert_eq!(nth_state(&0, step, 1_000_000_000), 4);
+    }
+}
@@ -1,90 +1,138 @@
 #![allow(dead_code)]
 
-#[cfg(test)]
-mod tests {
-    use std::{
-        collections::{HashMap, HashSet},
-        fs,
-    };
+use std::collections::HashSet;
 
-    use crate::grid::Grid;
-    type VGrid = Vec<Vec<char>>;
+use crate::{
+    grid::Grid,
+    input::InputError,
+    parsing::grid,
+    solution::{Answer, Solution},
+};
 
-    fn find_empty_rows_and_cols(grid: &VGrid) -> (HashSet<usize>, HashSet<usize>) {
-        let rows = grid.len();
-        let cols = grid[0].len();
+pub struct Day11;
 
-        let mut empty_rows: HashSet<usize> = HashSet::new();
-        let mut empty_cols: HashSet<usize> = HashSet::new();
+impl Solution for Day11 {
+    type Input = VGrid;
 
-        for (r, row) in grid.iter().enumerate() {
-            if row.iter().find(|c| **c != '.').is_none() {
-                empty_rows.insert(r);
-            }
-        }
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        grid(contents, |c| matches!(c, '.' | '#').then_some(c))
+    }
 
-        let mut found = false;
-        for c in 0..cols {
-            for r in 0..rows {
-                found = found
-                    || match grid[r][c] {
-                        '.' => false,
-                        _ => true,
-                    };
-            }
+    fn part1(input: &Self::Input) -> Answer {
+        sum_distances(input, 2).into()
+    }
 
-            if !found {
-                empty_cols.insert(c);
-                // break;
-            }
-            found = false;
+    fn part2(input: &Self::Input) -> Answer {
+        sum_distances(input, 1000000).into()
+    }
+}
+
+type VGrid = Vec<Vec<char>>;
+
+fn find_empty_rows_and_cols(grid: &VGrid) -> (HashSet<usize>, HashSet<usize>) {
+    let cols = grid[0].len();
+
+    let mut empty_rows: HashSet<usize> = HashSet::new();
+    let mut empty_cols: HashSet<usize> = HashSet::new();
+
+    for (r, row) in grid.iter().enumerate() {
+        if row.iter().find(|c| **c != '.').is_none() {
+            empty_rows.insert(r);
         }
-        (empty_rows, empty_cols)
     }
 
-    fn expand(grid: VGrid, scale: usize) -> VGrid {
-        let (empty_rows, empty_cols) = find_empty_rows_and_cols(&grid);
-        let g = grid
-            .iter()
-            .enumerate()
-            .flat_map(|(r, row)| {
-                if empty_rows.contains(&r) {
-                    let mut new_rows = Vec::new();
-                    for _ in 0..scale {
-                        new_rows.push(row.clone());
-                    }
-                    new_rows
-                } else {
-                    vec![row.clone()]
+    for c in 0..cols {
+        if grid.iter().all(|row| row[c] == '.') {
+            empty_cols.insert(c);
+        }
+    }
+    (empty_rows, empty_cols)
+}
+
+fn expand(grid: VGrid, scale: usize) -> VGrid {
+    let (empty_rows, empty_cols) = find_empty_rows_and_cols(&grid);
+    let g = grid
+        .iter()
+        .enumerate()
+        .flat_map(|(r, row)| {
+            if empty_rows.contains(&r) {
+                let mut new_rows = Vec::new();
+                for _ in 0..scale {
+                    new_rows.push(row.clone());
                 }
-            })
-            .map(|row| {
-                row.iter()
-                    .enumerate()
-                    .flat_map(|(c, col)| {
-                        if empty_cols.contains(&c) {
-                            let mut new_cols = Vec::new();
-                            for _ in 0..scale {
-                                new_cols.push(*col);
-                            }
-                            new_cols
-                        } else {
-                            vec![*col]
+                new_rows
+            } else {
+                vec![row.clone()]
+            }
+        })
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .flat_map(|(c, col)| {
+                    if empty_cols.contains(&c) {
+                        let mut new_cols = Vec::new();
+                        for _ in 0..scale {
+                            new_cols.push(*col);
                         }
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
+                        new_cols
+                    } else {
+                        vec![*col]
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
 
-        g
+    g
+}
+
+fn print(grid: &VGrid) {
+    for row in grid {
+        let s = row.iter().collect::<String>();
+        println!("{}", s);
     }
+}
 
-    fn print(grid: &VGrid) {
-        for row in grid {
-            let s = row.iter().collect::<String>();
-            println!("{}", s);
+/// Sum of the distances between every pair of galaxies, with every empty
+/// row/column counting as `scale` rows/columns.
+fn sum_distances(g: &VGrid, scale: i64) -> i64 {
+    let (empty_rows, empty_cols) = find_empty_rows_and_cols(g);
+
+    let stars = Grid::new(g.clone()).find_all(&'#');
+
+    let stars = stars
+        .iter()
+        .map(|(x, y)| {
+            let rinc = empty_rows.iter().filter(|r| **r < *y as usize).count() as i64;
+            let cinc = empty_cols.iter().filter(|c| **c < *x as usize).count() as i64;
+            (
+                *x as i64 + cinc * (scale - 1),
+                *y as i64 + rinc * (scale - 1),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut sum_dist: i64 = 0;
+    for (i, s1) in stars.iter().enumerate() {
+        for s2 in &stars[i + 1..] {
+            sum_dist += (s1.0 - s2.0).abs() + (s1.1 - s2.1).abs();
         }
     }
+    sum_dist
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        fs,
+    };
+
+    use crate::grid::Grid;
+
+    use crate::solution::Solution;
+
+    use super::{expand, find_empty_rows_and_cols, Day11};
 
     #[test]
     fn p1() {
@@ -212,4 +260,11 @@ mod tests {
 
         println!("sum_dist:{:?}", sum_dist / 2);
     }
+
+    #[test]
+    fn test_bad_input() {
+        let err = |contents| Day11::parse(contents).unwrap_err().to_string();
+        assert_eq!(err("..#\n.x.\n"), "line 2, column 2: unexpected `x`");
+        assert_eq!(err("..#\n.#\n"), "line 2: row has 2 cells, expected 3");
+    }
 }
@@ -1,142 +1,203 @@
 #![allow(dead_code)]
 
-static LOG: bool = false;
+use std::collections::HashMap;
 
-#[cfg(test)]
-mod tests {
-    use std::{collections::HashMap, fs};
+use crate::{
+    input::InputError,
+    parsing::{lines, number, pair},
+    solution::{Answer, Solution},
+};
 
-    use crate::day12::LOG;
+static LOG: bool = false;
 
-    fn max(d1: i64, d2: i64) -> i64 {
-        d1.max(d2)
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<(String, Vec<i64>)>;
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        lines(contents, |l| {
+            let (springs, groups) = pair(l, " ")?;
+            if let Some((i, c)) = springs
+                .char_indices()
+                .find(|(_, c)| !matches!(c, '.' | '#' | '?'))
+            {
+                return Err(InputError {
+                    col: Some(i + 1),
+                    ..InputError::new(format!("unexpected `{}`", c))
+                });
+            }
+            let groups = groups.split(',').map(number).collect::<Result<_, _>>()?;
+            Ok((springs.to_string(), groups))
+        })
     }
 
-    fn max3(d1: i64, d2: i64, d3: i64) -> i64 {
-        d1.max(d2).max(d3)
+    fn part1(input: &Self::Input) -> Answer {
+        input
+            .iter()
+            .map(|(left, groups)| {
+                solve(
+                    &left.chars().collect::<Vec<_>>(),
+                    groups.clone(),
+                    0,
+                    &mut HashMap::new(),
+                )
+            })
+            .sum::<i64>()
+            .into()
     }
 
-    type Groups = i64;
-    type Index = usize;
+    fn part2(input: &Self::Input) -> Answer {
+        input
+            .iter()
+            .map(|(left, groups)| {
+                let left = [left.as_str(); 5].join("?");
+                solve(
+                    &left.chars().collect::<Vec<_>>(),
+                    groups.repeat(5),
+                    0,
+                    &mut HashMap::new(),
+                )
+            })
+            .sum::<i64>()
+            .into()
+    }
+}
 
-    fn solve(
-        left: &Vec<char>,
-        groups: Vec<i64>,
-        i: Index,
-        cache: &mut HashMap<(String, Index), i64>,
-    ) -> i64 {
-        let sergroups = groups.iter().map(|c| format!("{}", c)).collect::<String>();
+fn max(d1: i64, d2: i64) -> i64 {
+    d1.max(d2)
+}
 
-        if LOG {
-            println!("{} {}", left.iter().skip(i).collect::<String>(), sergroups);
+fn max3(d1: i64, d2: i64, d3: i64) -> i64 {
+    d1.max(d2).max(d3)
+}
+
+type Groups = i64;
+type Index = usize;
+
+fn solve(
+    left: &Vec<char>,
+    groups: Vec<i64>,
+    i: Index,
+    cache: &mut HashMap<(String, Index), i64>,
+) -> i64 {
+    let sergroups = groups.iter().map(|c| format!("{}", c)).collect::<String>();
+
+    if LOG {
+        println!("{} {}", left.iter().skip(i).collect::<String>(), sergroups);
+    }
+    if groups.is_empty() {
+        let remaining = left.iter().skip(i).find(|c| **c == '#').is_some();
+        if remaining || left.len() < i {
+            return 0;
+        } else {
+            return 1;
         }
-        if groups.len() == 0 {
-            let remaining = left.iter().skip(i).find(|c| **c == '#').is_some();
-            if remaining || left.len() < i {
-                return 0;
-            } else {
-                return 1;
-            }
+    }
+
+    let mut next = match left.get(i) {
+        Some(el) => el,
+        None => {
+            cache.insert((sergroups.clone(), i), 0);
+            return 0;
         }
+    };
+    let mut i = i;
 
-        let mut next = match left.get(i) {
+    while *next != '?' && *next != '#' {
+        i += 1;
+        next = match left.get(i) {
             Some(el) => el,
             None => {
                 cache.insert((sergroups.clone(), i), 0);
                 return 0;
             }
-        };
-        let mut i = i;
-
-        while *next != '?' && *next != '#' {
-            i += 1;
-            next = match left.get(i) {
-                Some(el) => el,
-                None => {
-                    cache.insert((sergroups.clone(), i), 0);
-                    return 0;
-                }
-            }
         }
+    }
 
-        if cache.contains_key(&(sergroups.clone(), i)) {
-            return *cache.get(&(sergroups.clone(), i)).unwrap();
-        }
+    if cache.contains_key(&(sergroups.clone(), i)) {
+        return *cache.get(&(sergroups.clone(), i)).unwrap();
+    }
 
-        let group = *groups.get(0).unwrap();
+    let group = *groups.first().unwrap();
 
-        if *next == '#' {
-            let direct_matches = left.iter().skip(i).take_while(|c| **c == '#').count();
-            if (direct_matches as i64) > group {
-                if LOG {
-                    println!("Not enough direct matches");
-                }
-                cache.insert((sergroups.clone(), i), 0);
-                return 0;
+    if *next == '#' {
+        let direct_matches = left.iter().skip(i).take_while(|c| **c == '#').count();
+        if (direct_matches as i64) > group {
+            if LOG {
+                println!("Not enough direct matches");
             }
+            cache.insert((sergroups.clone(), i), 0);
+            return 0;
         }
+    }
 
-        let semi_direct_matches = left
-            .iter()
-            .skip(i)
-            .take_while(|c| **c == '#' || **c == '?')
-            .collect::<Vec<_>>();
-        if (semi_direct_matches.len() as i64) < group {
-            if semi_direct_matches.contains(&&'#') {
-                return 0;
-            }
-            let res = solve(left, groups, i + semi_direct_matches.len() + 1, cache);
-            cache.insert((sergroups.clone(), i), res);
-            return res;
+    let semi_direct_matches = left
+        .iter()
+        .skip(i)
+        .take_while(|c| **c == '#' || **c == '?')
+        .collect::<Vec<_>>();
+    if (semi_direct_matches.len() as i64) < group {
+        if semi_direct_matches.contains(&&'#') {
+            return 0;
         }
+        let res = solve(left, groups, i + semi_direct_matches.len() + 1, cache);
+        cache.insert((sergroups.clone(), i), res);
+        return res;
+    }
 
-        if *next == '?' {}
+    let next_is_pnd = left
+        .iter()
+        .skip(i)
+        .nth(group as usize)
+        .map(|c| *c == '#')
+        .unwrap_or(false);
 
-        let next_is_pnd = left
-            .iter()
-            .skip(i)
-            .skip(group as usize)
-            .next()
-            .map(|c| *c == '#')
-            .unwrap_or(false);
-
-        if next_is_pnd && LOG {
-            println!("next_is_pnd")
-        }
-
-        let pos_next_inc = i + (group as usize) + 1;
+    if next_is_pnd && LOG {
+        println!("next_is_pnd")
+    }
 
-        let cont = if next_is_pnd {
-            0
-        } else {
-            if LOG {
-                println!("pos_next_inc={}", pos_next_inc);
-            }
-            solve(
-                left,
-                groups[1..].to_vec(),
-                left.len().min(pos_next_inc),
-                cache,
-            )
-        };
-
-        let skip = if *next == '?' {
-            solve(left, groups, i + 1, cache)
-        } else {
-            0
-        };
-        let res = cont + skip;
+    let pos_next_inc = i + (group as usize) + 1;
 
-        cache.insert((sergroups.clone(), i), res);
+    let cont = if next_is_pnd {
+        0
+    } else {
         if LOG {
-            let slice = left.iter().skip(i).collect::<String>();
-            println!(
-                "left:{}, groups:{}, cont={}, skip={}, res={}",
-                slice, sergroups, cont, skip, res
-            );
+            println!("pos_next_inc={}", pos_next_inc);
         }
-        return res;
+        solve(
+            left,
+            groups[1..].to_vec(),
+            left.len().min(pos_next_inc),
+            cache,
+        )
+    };
+
+    let skip = if *next == '?' {
+        solve(left, groups, i + 1, cache)
+    } else {
+        0
+    };
+    let res = cont + skip;
+
+    cache.insert((sergroups.clone(), i), res);
+    if LOG {
+        let slice = left.iter().skip(i).collect::<String>();
+        println!(
+            "left:{}, groups:{}, cont={}, skip={}, res={}",
+            slice, sergroups, cont, skip, res
+        );
     }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use crate::solution::Solution;
+
+    use super::{solve, Day12};
 
     #[test]
     fn test_solve() {
@@ -181,7 +242,7 @@ mod tests {
                 &left.chars().collect::<Vec<_>>(),
                 right
                     .split(",")
-                    .map(|c| i64::from_str_radix(c, 10).unwrap())
+                    .map(|c| c.parse::<i64>().unwrap())
                     .collect::<Vec<_>>(),
                 0,
                 &mut HashMap::new(),
@@ -204,13 +265,13 @@ mod tests {
         let mut res = 0;
 
         for (left, right) in g {
-            let left = vec![left; 5].join("?");
-            let right = vec![right; 5].join(",");
+            let left = [left; 5].join("?");
+            let right = [right; 5].join(",");
             let subres = solve(
                 &left.chars().collect::<Vec<_>>(),
                 right
                     .split(",")
-                    .map(|c| i64::from_str_radix(c, 10).unwrap())
+                    .map(|c| c.parse::<i64>().unwrap())
                     .collect::<Vec<_>>(),
                 0,
                 &mut HashMap::new(),
@@ -221,4 +282,15 @@ mod tests {
 
         println!("\nres: {}", res);
     }
+
+    #[test]
+    fn test_bad_input() {
+        let err = |contents| Day12::parse(contents).unwrap_err().to_string();
+        assert_eq!(
+            err("???.### 1,1,3\n.??..?x 1,1"),
+            "line 2, column 7: unexpected `x`"
+        );
+        assert_eq!(err("???.### 1,,3"), "line 1: not a number: ``");
+        assert_eq!(err("???.###"), "line 1: expected ` ` in `???.###`");
+    }
 }
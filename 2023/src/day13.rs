@@ -1,118 +1,122 @@
 #![allow(dead_code)]
 
-#[cfg(test)]
-mod tests {
+use std::iter::zip;
 
-    use std::{fs, iter::zip, usize};
+use crate::{
+    input::InputError,
+    parsing::{blocks, char_grid},
+    solution::{Answer, Solution},
+};
 
-    type Problem = Vec<Vec<char>>;
+pub struct Day13;
 
-    fn parse_input() -> Vec<Problem> {
-        let contents = fs::read_to_string("./problems/d13.txt").expect("");
+impl Solution for Day13 {
+    type Input = Vec<Problem>;
 
-        let mut problems = Vec::new();
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        parse_input(contents)
+    }
 
-        let mut subp = Vec::new();
+    fn part1(input: &Self::Input) -> Answer {
+        summarize(input, 0).into()
+    }
 
-        for line in contents.lines() {
-            if line.trim().is_empty() {
-                problems.push(subp);
-                subp = Vec::new();
-            } else {
-                subp.push(line.chars().collect::<Vec<_>>())
-            }
-        }
+    fn part2(input: &Self::Input) -> Answer {
+        summarize(input, 1).into()
+    }
+}
 
-        if !subp.is_empty() {
-            problems.push(subp);
-        }
+type Problem = Vec<Vec<char>>;
+
+fn parse_input(contents: &str) -> Result<Vec<Problem>, InputError> {
+    blocks(contents, char_grid)
+}
+
+fn rotate(grid: &[Vec<char>]) -> Vec<Vec<char>> {
+    let width = grid[0].len();
+    (0..width)
+        .map(|w| grid.iter().rev().map(|row| row[w]).collect())
+        .collect()
+}
 
-        problems
+fn print(p: &Problem) {
+    println!();
+    for l in p {
+        let s = l.iter().collect::<String>();
+        println!("{}", s);
     }
+}
+
+fn count_diffs(cols: usize, rows: usize, p: &Problem) -> usize {
+    // for line in p {
+    let line = &p[rows];
+    let line_and_ind = line.iter().enumerate().collect::<Vec<_>>();
+    let (left, right) = line_and_ind.split_at(cols);
 
-    fn rotate(grid: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-        let height = grid.len();
-        let width = grid[0].len();
+    let diffs = zip(left.iter().rev(), right)
+        .map(|(lc, rc)| lc.1 == rc.1)
+        .filter(|e| !*e)
+        .collect::<Vec<_>>();
 
-        let mut v: Vec<Vec<char>> = Vec::new();
+    diffs.len()
+}
 
-        for w in 0..width {
-            let mut row: Vec<_> = Vec::new();
-            for h in 0..height {
-                // println!("{:?}")
-                row.insert(0, grid[h][w]);
+/// `part` is the number of smudges the mirror line has to fix.
+fn summarize(inp: &[Problem], part: usize) -> usize {
+    let mut total_res = 0;
+    for p in inp {
+        let mut hsplits = 0;
+        let mut wsplits = 0;
+
+        if let Some(fline) = p.first() {
+            for i in 1..fline.len() {
+                let mut sum_diff = 0;
+                for j in 0..p.len() {
+                    sum_diff += count_diffs(i, j, p);
+                }
+
+                if sum_diff == part {
+                    hsplits = i;
+                }
             }
-            v.push(row)
         }
 
-        v
-    }
+        let pt = rotate(p);
 
-    fn print(p: &Problem) {
-        println!();
-        for l in p {
-            let s = l.iter().collect::<String>();
-            println!("{}", s);
+        if let Some(fline) = pt.first() {
+            for i in 1..fline.len() {
+                let mut sum_diff = 0;
+                for j in 0..pt.len() {
+                    sum_diff += count_diffs(i, j, &pt);
+
+                    // println!("diff: {}", sum_diff);
+                }
+
+                if sum_diff == part {
+                    wsplits += fline.len() - i;
+                }
+            }
         }
+
+        total_res += hsplits + (100 * wsplits);
     }
+    total_res
+}
 
-    fn count_diffs(cols: usize, rows: usize, p: &Problem) -> usize {
-        // for line in p {
-        let line = &p[rows];
-        let line_and_ind = line.iter().enumerate().collect::<Vec<_>>();
-        let (left, right) = line_and_ind.split_at(cols);
+#[cfg(test)]
+mod tests {
 
-        let diffs = zip(left.iter().rev(), right)
-            .map(|(lc, rc)| lc.1 == rc.1)
-            .filter(|e| *e == false)
-            .collect::<Vec<_>>();
+    use std::fs;
 
-        let diff = diffs.len();
-        diff
-    }
+    use super::{parse_input, summarize};
 
     #[test]
     fn p1_and_p2() {
-        let inp = parse_input();
+        let contents = fs::read_to_string("./problems/d13.txt").expect("");
+        let inp = parse_input(&contents).unwrap();
 
         for part in [0, 1] {
-            let mut total_res = 0;
-            for p in &inp {
-                let mut hsplits = 0;
-                let mut wsplits = 0;
-
-                p.get(0).map(|fline| {
-                    for i in 1..fline.len() {
-                        let mut sum_diff = 0;
-                        for j in 0..p.len() {
-                            sum_diff += count_diffs(i, j, &p);
-                        }
-
-                        if sum_diff == part {
-                            hsplits = i;
-                        }
-                    }
-                });
-
-                let pt = rotate(&p);
-
-                pt.get(0).map(|fline| {
-                    for i in 1..fline.len() {
-                        let mut sum_diff = 0;
-                        for j in 0..pt.len() {
-                            sum_diff += count_diffs(i, j, &pt);
-
-                            // println!("diff: {}", sum_diff);
-                        }
-
-                        if sum_diff == part {
-                            wsplits += fline.len() - i;
-                        }
-                    }
-                });
-
-                total_res += hsplits + (100 * wsplits);
-            }
+            let total_res = summarize(&inp, part);
             println!("res: {}", total_res)
         }
     }
@@ -1,200 +1,115 @@
 #![allow(dead_code)]
 
-#[cfg(test)]
-mod tests {
-    use std::{
-        collections::{BinaryHeap, HashSet},
-        fs,
-    };
+use crate::{
+    grid::Grid,
+    input::InputError,
+    point::{Direction, Point},
+    search,
+    solution::{Answer, Solution},
+};
 
-    use crate::grid::Grid;
+pub struct Day17;
 
-    type Coord = (i32, i32);
+impl Solution for Day17 {
+    type Input = HGrid;
 
-    struct HGrid(Grid<i32>);
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        Ok(HGrid(Grid::from_digits(contents)?))
+    }
 
-    impl HGrid {
-        fn print(&self) {
-            self.0.print();
-        }
+    fn part1(input: &Self::Input) -> Answer {
+        min_heat_loss(input, 1, 3).into()
+    }
 
-        fn rows(&self) -> usize {
-            self.0._grid.len()
-        }
+    fn part2(input: &Self::Input) -> Answer {
+        min_heat_loss(input, 4, 10).into()
+    }
+}
 
-        fn cols(&self) -> usize {
-            self.0._grid[0].len()
-        }
+pub struct HGrid(Grid<i32>);
 
-        fn in_range(&self, row: i32, col: i32) -> bool {
-            row >= 0 && row < self.rows() as i32 && col >= 0 && col < self.cols() as i32
-        }
+impl HGrid {
+    fn print(&self) {
+        self.0.print();
+    }
 
-        fn print_mem(mem: &Vec<Vec<Vec<i32>>>, level: usize) {
-            for r in mem {
-                for c in r {
-                    print!("{:>3}", c[level])
-                }
-                println!();
+    fn rows(&self) -> usize {
+        self.0._grid.len()
+    }
+
+    fn cols(&self) -> usize {
+        self.0._grid[0].len()
+    }
+
+    fn print_mem(mem: &Vec<Vec<Vec<i32>>>, level: usize) {
+        for r in mem {
+            for c in r {
+                print!("{:>3}", c[level])
             }
+            println!();
         }
     }
+}
 
-    #[test]
-    fn p1() {
-        let contents = fs::read_to_string("./problems/d17.txt").expect("");
-        let grid: HGrid = HGrid(Grid::new(
-            contents
-                .split("\n")
-                .map(|l| {
-                    l.split("")
-                        .filter(|e| !e.is_empty())
-                        .map(|c| {
-                            i32::from_str_radix(format!("{}", c).as_str(), 10)
-                                .expect(format!("can parse {}", c).as_str())
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>(),
-        ));
-
-        let mut heap2: BinaryHeap<(i32, i32, i32, i32, i32, i32)> = BinaryHeap::new();
-
-        heap2.push((0, 0, 0, 0, 0, 0));
-
-        let mut visited2 = HashSet::new();
-
-        while let Some((h, row, col, dr, dc, s)) = heap2.pop() {
-            let h = -1 * h;
-            if !grid.in_range(row, col) {
-                continue;
+/// Where the crucible is, where it's heading and how far it went in a
+/// straight line. The heading is `None` only at the start, where the crucible
+/// can go anywhere.
+type Crucible = (Point, Option<Direction>, i32);
+
+/// The crucible has to move at least `min_steps` before turning or stopping at
+/// the end, and can't go further than `max_steps` in a straight line.
+fn min_heat_loss(grid: &HGrid, min_steps: i32, max_steps: i32) -> i32 {
+    let end = Point::new(grid.cols() as i32 - 1, grid.rows() as i32 - 1);
+
+    let successors = |&(pos, dir, s): &Crucible| {
+        let moves = match dir {
+            Some(dir) => {
+                let mut moves = Vec::new();
+                if s < max_steps {
+                    moves.push((dir, s + 1));
+                }
+                if s >= min_steps {
+                    moves.push((dir.turn_left(), 1));
+                    moves.push((dir.turn_right(), 1));
+                }
+                moves
             }
+            None => Direction::ALL.map(|dir| (dir, 1)).to_vec(),
+        };
+        moves
+            .into_iter()
+            .filter_map(move |(dir, s)| grid.0.step(pos, dir).map(|next| (next, Some(dir), s)))
+    };
 
-            if row == grid.rows() as i32 - 1 && col == grid.cols() as i32 - 1 {
-                println!("res={}", h);
-                break;
-            }
+    search::dijkstra(
+        &(Point::ORIGIN, None, 0),
+        successors,
+        |_, (next, _, _)| grid.0[*next],
+        |&(pos, _, s)| pos == end && s >= min_steps,
+    )
+    .map(|(_, heat_loss)| heat_loss)
+    .expect("the bottom right corner is always reachable")
+}
 
-            let key = (row, col, dr, dc, s);
+#[cfg(test)]
+mod tests {
+    use std::fs;
 
-            if visited2.contains(&key) {
-                continue;
-            }
-            visited2.insert(key);
-
-            if s < 3 && (dr, dc) != (0, 0) {
-                let next_row = row as i32 + dr;
-                let next_col = col as i32 + dc;
-                if grid.in_range(next_row, next_col) {
-                    heap2.push((
-                        -1 * (h + grid.0._grid[next_row as usize][next_col as usize]),
-                        next_row,
-                        next_col,
-                        dr,
-                        dc,
-                        s + 1,
-                    ))
-                }
-            }
+    use crate::solution::Solution;
 
-            for (next_dr, next_dc) in vec![(0, 1), (1, 0), (0, -1), (-1, 0)] {
-                if (next_dr, next_dc) != (dr, dc) && (next_dr, next_dc) != (-dr, -dc) {
-                    let next_row = row + next_dr;
-                    let next_col = col + next_dc;
-                    if grid.in_range(next_row, next_col) {
-                        heap2.push((
-                            -1 * (h + grid.0._grid[next_row as usize][next_col as usize]),
-                            next_row,
-                            next_col,
-                            next_dr,
-                            next_dc,
-                            1,
-                        ))
-                    }
-                }
-            }
-        }
+    use super::Day17;
 
-        println!("Done");
+    #[test]
+    fn p1() {
+        let contents = fs::read_to_string("./problems/d17.txt").expect("");
+        let h = Day17::part1(&Day17::parse(&contents).unwrap());
+        println!("res={}", h);
     }
 
     #[test]
     fn p2() {
         let contents = fs::read_to_string("./problems/d17.txt").expect("");
-        let grid: HGrid = HGrid(Grid::new(
-            contents
-                .split("\n")
-                .map(|l| {
-                    l.split("")
-                        .filter(|e| !e.is_empty())
-                        .map(|c| {
-                            i32::from_str_radix(format!("{}", c).as_str(), 10)
-                                .expect(format!("can parse {}", c).as_str())
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>(),
-        ));
-
-        let mut heap2: BinaryHeap<(i32, i32, i32, i32, i32, i32)> = BinaryHeap::new();
-
-        heap2.push((0, 0, 0, 0, 0, 0));
-
-        let mut visited2 = HashSet::new();
-
-        while let Some((h, row, col, dr, dc, s)) = heap2.pop() {
-            let h = -1 * h;
-            if !grid.in_range(row, col) {
-                continue;
-            }
-
-            if row == grid.rows() as i32 - 1 && col == grid.cols() as i32 - 1 {
-                println!("res={}", h);
-                break;
-            }
-
-            let key = (row, col, dr, dc, s);
-
-            if visited2.contains(&key) {
-                continue;
-            }
-            visited2.insert(key);
-
-            if s < 10 && (dr, dc) != (0, 0) {
-                let next_row = row as i32 + dr;
-                let next_col = col as i32 + dc;
-                if grid.in_range(next_row, next_col) {
-                    heap2.push((
-                        -1 * (h + grid.0._grid[next_row as usize][next_col as usize]),
-                        next_row,
-                        next_col,
-                        dr,
-                        dc,
-                        s + 1,
-                    ))
-                }
-            }
-
-            if s > 3 || (dr, dc) == (0, 0) {
-                for (next_dr, next_dc) in vec![(0, 1), (1, 0), (0, -1), (-1, 0)] {
-                    if (next_dr, next_dc) != (dr, dc) && (next_dr, next_dc) != (-dr, -dc) {
-                        let next_row = row + next_dr;
-                        let next_col = col + next_dc;
-                        if grid.in_range(next_row, next_col) {
-                            heap2.push((
-                                -1 * (h + grid.0._grid[next_row as usize][next_col as usize]),
-                                next_row,
-                                next_col,
-                                next_dr,
-                                next_dc,
-                                1,
-                            ))
-                        }
-                    }
-                }
-            }
-        }
-
-        println!("Done");
+        let h = Day17::part2(&Day17::parse(&contents).unwrap());
+        println!("res={}", h);
     }
 }
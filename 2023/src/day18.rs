@@ -1,253 +1,215 @@
 #![allow(dead_code)]
 
-#[cfg(test)]
-mod tests {
-    use std::{collections::HashSet, fs};
-
-    use crate::grid::Grid;
-
-    enum Dir {
-        L,
-        D,
-        R,
-        U,
+use std::collections::HashSet;
+
+use crate::{
+    grid::Grid,
+    input::InputError,
+    parsing::{lines, number},
+    point::{Direction, Point},
+    polygon::Polygon,
+    solution::{Answer, Solution},
+};
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    /// Direction and distance of each step, and the step hidden in its color
+    /// code
+    type Input = Vec<(Direction, i32, (Direction, i32))>;
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        lines(contents, |line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let [dir, dist, color] = fields[..] else {
+                return Err(InputError::new(format!(
+                    "expected direction, distance and color in `{}`",
+                    line
+                )));
+            };
+            let dir = dir
+                .chars()
+                .next()
+                .and_then(Direction::from_char)
+                .filter(|_| dir.len() == 1)
+                .ok_or(InputError::new(format!("unknown direction `{}`", dir)))?;
+            Ok((dir, number(dist)?, from_color(color)?))
+        })
     }
 
-    impl Dir {
-        fn to_direction(&self) -> (i32, i32) {
-            match self {
-                Dir::L => (0, -1),
-                Dir::D => (1, 0),
-                Dir::R => (0, 1),
-                Dir::U => (-1, 0),
-            }
-        }
+    fn part1(input: &Self::Input) -> Answer {
+        solve2(input.iter().map(|(dir, dist, _)| (*dir, *dist)).collect()).into()
     }
 
-    fn solve(data: Vec<(Dir, i32, &str)>) {
-        let mut row = 0;
-        let mut col = 0;
-
-        let mut max_row = i32::MIN;
-        let mut max_col = i32::MIN;
-        let mut min_row = i32::MAX;
-        let mut min_col = i32::MAX;
-
-        for (dir, dist, _) in &data {
-            (row, col) = match dir {
-                Dir::D => (row + dist, col),
-                Dir::U => (row - dist, col),
-                Dir::L => (row, col - dist),
-                Dir::R => (row, col + dist),
-            };
-            max_row = max_row.max(row);
-            max_col = max_col.max(col);
-            min_row = min_row.min(row);
-            min_col = min_col.min(col);
-        }
-
-        let rows = min_row.abs_diff(max_row + 1);
-        let cols = min_col.abs_diff(max_col + 1);
-        let offset_row = min_row.abs_diff(0);
-        let offset_col = min_col.abs_diff(0);
+    fn part2(input: &Self::Input) -> Answer {
+        solve2(input.iter().map(|(_, _, step)| *step).collect()).into()
+    }
+}
 
-        println!("{},{}", rows, cols);
-        println!("offsets: {},{}", offset_row, offset_col);
+/// The real instructions are hidden in the color: five hex digits for the
+/// distance followed by one digit for the direction.
+fn from_color(color: &str) -> Result<(Direction, i32), InputError> {
+    let bad_color = || {
+        InputError::new(format!(
+            "expected a color like `(#70c710)`, found `{}`",
+            color
+        ))
+    };
+    let hex = color
+        .strip_prefix("(#")
+        .and_then(|c| c.strip_suffix(')'))
+        .filter(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(bad_color)?;
+    let dir = match &hex[5..6] {
+        "0" => Direction::Right,
+        "1" => Direction::Down,
+        "2" => Direction::Left,
+        "3" => Direction::Up,
+        _ => return Err(bad_color()),
+    };
+    let dist = i32::from_str_radix(&hex[0..5], 16).map_err(|_| bad_color())?;
+    Ok((dir, dist))
+}
 
-        let mut grid = vec![vec!['.'; cols as usize]; rows as usize];
+/// Fills the lagoon on a grid, only practical for the small part 1 plan.
+fn solve(data: Vec<(Direction, i32)>) -> i32 {
+    let mut pos = Point::ORIGIN;
+
+    let mut max_row = i32::MIN;
+    let mut max_col = i32::MIN;
+    let mut min_row = i32::MAX;
+    let mut min_col = i32::MAX;
+
+    for (dir, dist) in &data {
+        pos = pos.step_n(*dir, *dist);
+        max_row = max_row.max(pos.y);
+        max_col = max_col.max(pos.x);
+        min_row = min_row.min(pos.y);
+        min_col = min_col.min(pos.x);
+    }
 
-        let mut row = offset_row as i32;
-        let mut col = offset_col as i32;
-        for (dir, dist, _) in &data {
-            let (nrow, ncol) = match dir {
-                Dir::D => (row + dist, col),
-                Dir::U => (row - dist, col),
-                Dir::L => (row, col - dist),
-                Dir::R => (row, col + dist),
-            };
+    let rows = min_row.abs_diff(max_row + 1);
+    let cols = min_col.abs_diff(max_col + 1);
+    let offset_row = min_row.abs_diff(0);
+    let offset_col = min_col.abs_diff(0);
 
-            let nrow = nrow as usize;
-            let ncol = ncol as usize;
-            let crow = row as usize;
-            let ccol = col as usize;
+    let mut grid = vec![vec!['.'; cols as usize]; rows as usize];
 
-            // let mrow = crow.min(nrow);
+    let mut row = offset_row as i32;
+    let mut col = offset_col as i32;
+    for (dir, dist) in &data {
+        let next = Point::new(col, row).step_n(*dir, *dist);
 
-            // println!("{:?}", crow..nrow);
+        let nrow = next.y as usize;
+        let ncol = next.x as usize;
+        let crow = row as usize;
+        let ccol = col as usize;
 
-            if crow < nrow {
-                for i in crow..nrow + 1 {
-                    grid[i][ccol] = '#';
-                }
-            }
+        // let mrow = crow.min(nrow);
 
-            if crow > nrow {
-                for i in nrow..crow + 1 {
-                    grid[i][ccol] = '#';
-                }
-            }
+        // println!("{:?}", crow..nrow);
 
-            if ccol < ncol {
-                for i in ccol..ncol + 1 {
-                    grid[crow][i] = '#';
-                }
+        if crow < nrow {
+            for line in &mut grid[crow..=nrow] {
+                line[ccol] = '#';
             }
+        }
 
-            if ccol > ncol {
-                for i in ncol..ccol + 1 {
-                    grid[crow][i] = '#';
-                }
+        if crow > nrow {
+            for line in &mut grid[nrow..=crow] {
+                line[ccol] = '#';
             }
+        }
 
-            row = nrow as i32;
-            col = ncol as i32;
+        if ccol < ncol {
+            grid[crow][ccol..=ncol].fill('#');
         }
 
-        // for row in &grid {
-        //     println!("{}", row.iter().collect::<String>());
-        // }
+        if ccol > ncol {
+            grid[crow][ncol..=ccol].fill('#');
+        }
 
-        let mut g = Grid::new(grid);
-        fill(&mut g);
+        row = nrow as i32;
+        col = ncol as i32;
+    }
 
-        // g.print();
+    // for row in &grid {
+    //     println!("{}", row.iter().collect::<String>());
+    // }
 
-        for (col, row) in g.find_all(&'.') {
-            g.set(col, row, '#');
-        }
+    let mut g = Grid::new(grid);
+    fill(&mut g);
 
-        // g.print();
+    // g.print();
 
-        println!("res={}", g.find_all(&'#').len() as i32);
+    for (col, row) in g.find_all(&'.') {
+        g.set(col, row, '#');
     }
 
-    fn solve2(data: Vec<(Dir, i32, &str)>) {
-        let mut row = 0;
-        let mut col = 0;
-
-        let mut max_row = i32::MIN;
-        let mut max_col = i32::MIN;
-        let mut min_row = i32::MAX;
-        let mut min_col = i32::MAX;
-
-        let mut vertices = Vec::new();
-        let mut boundaries: f64 = 0.0;
-        for (dir, dist, _) in &data {
-            let (nrow, ncol) = match dir {
-                Dir::D => (row + dist, col),
-                Dir::U => (row - dist, col),
-                Dir::L => (row, col - dist),
-                Dir::R => (row, col + dist),
-            };
-
-            boundaries += *dist as f64;
-
-            max_row = max_row.max(nrow);
-            max_col = max_col.max(ncol);
-            min_row = min_row.min(nrow);
-            min_col = min_col.min(ncol);
+    // g.print();
 
-            vertices.push((nrow as f64, ncol as f64));
+    g.find_all(&'#').len() as i32
+}
 
-            row = nrow as i32;
-            col = ncol as i32;
-        }
+/// Area of the lagoon, trench included, counted as the lattice points of the
+/// polygon the trench follows.
+fn solve2(data: Vec<(Direction, i32)>) -> i64 {
+    let moves = data.into_iter().map(|(dir, dist)| (dir, dist as i64));
+    Polygon::from_moves(moves).lattice_points() as i64
+}
 
-        let area = shoelace_formula(&vertices);
-        println!("Inner area:{:?}", area);
-        println!("Perimeter: {:?}", boundaries);
-        println!("Area: {:?}", picks_formula(boundaries, area));
+fn fill(grid: &mut Grid<char>) {
+    for row in 0..grid.rows() {
+        grid.flood(0, row, HashSet::from(['.']), 'P');
+        grid.flood(grid.cols() - 1, row, HashSet::from(['.']), 'P');
     }
-
-    fn picks_formula(bounderies: f64, area: f64) -> f64 {
-        let i = area - bounderies / 2.0 + 1.0;
-        i + bounderies
+    for col in 0..grid.cols() {
+        grid.flood(col, 0, HashSet::from(['.']), 'P');
+        grid.flood(col, grid.rows() - 1, HashSet::from(['.']), 'P');
     }
+}
 
-    fn shoelace_formula(coordinates: &Vec<(f64, f64)>) -> f64 {
-        let mut area: f64 = 0.0;
-        let n = coordinates.len();
-
-        // Sum over the main diagonal
-        for i in 0..n - 1 {
-            area += coordinates[i].0 * coordinates[i + 1].1;
-        }
-        area += coordinates[n - 1].0 * coordinates[0].1; // Close the polygon
+#[cfg(test)]
+mod tests {
+    use std::fs;
 
-        // Subtract the other diagonal
-        for i in 0..n - 1 {
-            area -= coordinates[i + 1].0 * coordinates[i].1;
-        }
-        area -= coordinates[0].0 * coordinates[n - 1].1; // Close the polygon
+    use crate::solution::Solution;
 
-        // Taking absolute value and dividing by 2
-        area.abs() / 2.0
-    }
+    use super::{solve, Day18};
 
     #[test]
     fn p1() {
         let contents = fs::read_to_string("./problems/d18.txt").expect("");
+        let input = Day18::parse(&contents).unwrap();
 
-        let data: Vec<(Dir, i32, &str)> = contents
-            .lines()
-            .map(|line| line.split_whitespace().collect::<Vec<_>>())
-            .map(|fields| {
-                (
-                    match fields[0] {
-                        "R" => Dir::R,
-                        "D" => Dir::D,
-                        "L" => Dir::L,
-                        "U" => Dir::U,
-                        _ => unreachable!(),
-                    },
-                    i32::from_str_radix(fields[1], 10).unwrap(),
-                    fields[2],
-                )
-            })
-            .collect::<Vec<_>>();
-
-        solve2(data);
+        println!("res={}", Day18::part1(&input));
+        println!(
+            "res={}",
+            solve(input.iter().map(|(dir, dist, _)| (*dir, *dist)).collect())
+        );
     }
 
     #[test]
     fn p2() {
         let contents = fs::read_to_string("./problems/d18.txt").expect("");
-
-        let data = contents
-            .lines()
-            .map(|line| line.split_whitespace().collect::<Vec<_>>())
-            .map(|fields| fields[2].replace("(#", "").replace("(", ""))
-            .map(|fields| {
-                println!(
-                    "{},{}",
-                    &fields[5..6],
-                    i32::from_str_radix(&fields[0..5], 16).unwrap()
-                );
-                (
-                    match &fields[5..6] {
-                        "0" => Dir::R,
-                        "1" => Dir::D,
-                        "2" => Dir::L,
-                        "3" => Dir::U,
-                        _ => unreachable!(),
-                    },
-                    i32::from_str_radix(&fields[0..5], 16).unwrap(),
-                    "   ",
-                )
-            })
-            .collect::<Vec<_>>();
-
-        solve2(data);
+        println!("res={}", Day18::part2(&Day18::parse(&contents).unwrap()));
     }
 
-    fn fill(grid: &mut Grid<char>) {
-        for row in 0..grid.rows() {
-            grid.flood(0, row, HashSet::from(['.']), 'P');
-            grid.flood(grid.cols() - 1, row, HashSet::from(['.']), 'P');
-        }
-        for col in 0..grid.cols() {
-            grid.flood(col, 0, HashSet::from(['.']), 'P');
-            grid.flood(col, grid.rows() - 1, HashSet::from(['.']), 'P');
-        }
+    #[test]
+    fn test_bad_input() {
+        let err = |contents| Day18::parse(contents).unwrap_err().to_string();
+        assert_eq!(
+            err("R 6 (#70c710)\nX 5 (#0dc571)"),
+            "line 2: unknown direction `X`"
+        );
+        assert_eq!(err("R x (#70c710)"), "line 1: not a number: `x`");
+        assert_eq!(
+            err("R 6 (#70c714)"),
+            "line 1: expected a color like `(#70c710)`, found `(#70c714)`"
+        );
+        assert_eq!(
+            err("R 6"),
+            "line 1: expected direction, distance and color in `R 6`"
+        );
     }
 }
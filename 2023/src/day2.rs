@@ -1,66 +1,87 @@
-#[cfg(test)]
-mod tests {
+use crate::{
+    input::InputError,
+    parsing::{lines, number, pair},
+    solution::{Answer, Solution},
+};
 
-    use crate::read_file;
+pub struct Day2;
 
-    fn to_color(v: &str) -> [u32; 3] {
-        let (count_str, color) = v.trim().split_once(" ").unwrap();
-        let count = count_str.parse().unwrap();
-        match color {
-            "red" => [count, 0, 0],
-            "green" => [0, count, 0],
-            "blue" => [0, 0, count],
-            _ => panic!("Unknown color"),
-        }
+impl Solution for Day2 {
+    type Input = Vec<[u32; 3]>;
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        lines(contents, p1_line)
     }
 
-    fn max(a: u32, b: u32) -> u32 {
-        if a > b {
-            a
-        } else {
-            b
-        }
+    fn part1(input: &Self::Input) -> Answer {
+        input
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| l[0] <= 12 && l[1] <= 13 && l[2] <= 14)
+            .map(|(i, _)| i + 1)
+            .sum::<usize>()
+            .into()
     }
 
-    fn p1_line(str: String) -> [u32; 3] {
-        let (_, n): (&str, &str) = str.split_once(":").unwrap();
-
-        let sets: [u32; 3] = n
-            .split(";")
-            .map(|s| {
-                s.trim()
-                    .split(',')
-                    .map(|c| to_color(c))
-                    .fold([0, 0, 0], |a, c| {
-                        [max(a[0], c[0]), max(a[1], c[1]), max(a[2], c[2])]
-                    })
-            })
-            .fold([0, 0, 0], |a, c| {
-                [max(a[0], c[0]), max(a[1], c[1]), max(a[2], c[2])]
-            });
-
-        return sets;
+    fn part2(input: &Self::Input) -> Answer {
+        input.iter().map(|l| l[0] * l[1] * l[2]).sum::<u32>().into()
     }
+}
+
+fn to_color(v: &str) -> Result<[u32; 3], InputError> {
+    let (count, color) = pair(v.trim(), " ")?;
+    let count = number(count)?;
+    match color {
+        "red" => Ok([count, 0, 0]),
+        "green" => Ok([0, count, 0]),
+        "blue" => Ok([0, 0, count]),
+        _ => Err(InputError::new(format!("unknown color `{}`", color))),
+    }
+}
+
+fn max(a: u32, b: u32) -> u32 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+/// The most cubes of each color shown at once in a game.
+fn p1_line(line: &str) -> Result<[u32; 3], InputError> {
+    let (_, n) = pair(line, ":")?;
+
+    let mut most = [0, 0, 0];
+    for set in n.split(';') {
+        for c in set.split(',') {
+            let c = to_color(c)?;
+            most = [max(most[0], c[0]), max(most[1], c[1]), max(most[2], c[2])];
+        }
+    }
+    Ok(most)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{input::read_lines, solution::Solution};
+
+    use super::{p1_line, Day2};
 
     #[test]
     fn p1() {
-        let mut i = 1;
         let mut res = 0;
-        for line in read_file::read_file("./problems/d2.txt".to_string()) {
-            let locations = p1_line(line);
+        for (i, line) in (1..).zip(read_lines("./problems/d2.txt").unwrap()) {
+            let locations = p1_line(&line).unwrap();
 
             println!(
-                "{}",
-                format!(
-                    "R={}, G={}, B={}\n",
-                    locations[0], locations[1], locations[2]
-                )
+                "R={}, G={}, B={}\n",
+                locations[0], locations[1], locations[2]
             );
 
             if locations[0] <= 12 && locations[1] <= 13 && locations[2] <= 14 {
                 res += i;
             }
-            i += 1;
         }
         println!("Res: {}", res);
     }
@@ -68,15 +89,12 @@ mod tests {
     #[test]
     fn p2() {
         let mut res = 0;
-        for line in read_file::read_file("./problems/d2.txt".to_string()) {
-            let locations = p1_line(line);
+        for line in read_lines("./problems/d2.txt").unwrap() {
+            let locations = p1_line(&line).unwrap();
 
             println!(
-                "{}",
-                format!(
-                    "R={}, G={}, B={}\n",
-                    locations[0], locations[1], locations[2]
-                )
+                "R={}, G={}, B={}\n",
+                locations[0], locations[1], locations[2]
             );
 
             let power = locations[0] * locations[1] * locations[2];
@@ -85,4 +103,18 @@ mod tests {
         }
         println!("Res: {}", res);
     }
+
+    #[test]
+    fn test_bad_input() {
+        let err = |contents| Day2::parse(contents).unwrap_err().to_string();
+        assert_eq!(
+            err("Game 1: 3 blue, 4 red\nGame 2: 1 purple"),
+            "line 2: unknown color `purple`"
+        );
+        assert_eq!(err("Game 1: blue 3"), "line 1: not a number: `blue`");
+        assert_eq!(
+            err("Game 1 3 blue"),
+            "line 1: expected `:` in `Game 1 3 blue`"
+        );
+    }
 }
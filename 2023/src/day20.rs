@@ -1,304 +1,114 @@
-#![allow(dead_code)]
+use crate::{
+    circuit::{Circuit, Pulse, Watch},
+    dot::Dot,
+    input::InputError,
+    math,
+    solution::{Answer, Solution},
+};
 
-#[cfg(test)]
-mod tests {
-    use num_integer::lcm;
-    use std::{
-        collections::{HashMap, HashSet, VecDeque},
-        fmt::Display,
-        fs,
-    };
+pub struct Day20;
 
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    enum ModuleType {
-        FlipFlop(bool),
-        Conjunction,
-        Broadcaster,
-        Other,
-    }
+impl Solution for Day20 {
+    type Input = Circuit;
 
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    struct Module {
-        name: String,
-        module_type: ModuleType,
-        targets: Vec<String>,
-        initial_state: bool,
-        last_pulse_received: HashMap<String, Pulse>,
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        Circuit::parse(contents)
     }
 
-    impl Display for Module {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let last_received = self
-                .last_pulse_received
-                .iter()
-                .map(|(k, v)| format!("{}={}", k, v))
-                .collect::<Vec<_>>()
-                .join(",");
-            let md = match self.module_type {
-                ModuleType::FlipFlop(st) => {
-                    format!("%({})", if st == false { "off" } else { "on" })
-                }
-                ModuleType::Conjunction => format!("&({})", last_received),
-                ModuleType::Broadcaster => format!(""),
-                ModuleType::Other => format!(""),
-            };
-            write!(f, "{} {} {{ {} }}", md, self.name, self.initial_state)
-        }
+    fn part1(input: &Self::Input) -> Answer {
+        let (high_pulses, low_pulses) = count_pulses(&mut input.clone(), 1000);
+        (high_pulses * low_pulses).into()
     }
 
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    enum Pulse {
-        High,
-        Low,
+    fn part2(input: &Self::Input) -> Answer {
+        presses_until_rx(input).map_or_else(Answer::from, Answer::from)
     }
 
-    impl Display for Pulse {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            match self {
-                Pulse::High => write!(f, "High"),
-                Pulse::Low => write!(f, "Low"),
-            }
-        }
+    fn dot(input: &Self::Input) -> Option<Dot> {
+        Some(input.to_dot())
     }
+}
 
-    impl Module {
-        fn from_str(s: &str, targets: Vec<&str>) -> Self {
-            let targets = targets
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>();
-
-            let module_type = if s.starts_with("%") {
-                ModuleType::FlipFlop(false)
-            } else if s.starts_with("&") {
-                ModuleType::Conjunction
-            } else if s.starts_with("broadcaster") {
-                ModuleType::Broadcaster
-            } else {
-                ModuleType::Other
-            };
-
-            Self {
-                name: s.replace("%", "").replace("&", "").to_string(),
-                module_type,
-                targets,
-                initial_state: true,
-                last_pulse_received: HashMap::new(),
-            }
-        }
-    }
-
-    impl Module {
-        fn get_name(&self) -> &String {
-            &self.name
-        }
-
-        fn fire(&self, pulse: Pulse) -> VecDeque<(String, Pulse)> {
-            self.targets
-                .iter()
-                .map(|n| (n.clone(), pulse.clone()))
-                .collect()
-        }
-
-        fn conj_pulse_to_fire(&self) -> Pulse {
-            if self.last_pulse_received.values().all(|v| *v == Pulse::High) {
-                Pulse::Low
-            } else {
-                Pulse::High
-            }
-        }
-
-        fn process(&mut self, from: &String, pulse: Pulse) -> VecDeque<(String, Pulse)> {
-            // println!("Process: {:?}, pulse:{:?}", self, pulse);
-
-            match self.module_type {
-                ModuleType::FlipFlop(curr_state) => match pulse {
-                    Pulse::High => VecDeque::new(),
-                    Pulse::Low => {
-                        self.module_type = ModuleType::FlipFlop(!curr_state);
-                        self.initial_state = !curr_state == false;
-                        self.fire(if curr_state == false {
-                            Pulse::High
-                        } else {
-                            Pulse::Low
-                        })
-                    }
-                },
-                ModuleType::Conjunction => {
-                    self.last_pulse_received.insert(from.clone(), pulse.clone());
-                    self.initial_state =
-                        self.last_pulse_received.values().all(|v| *v == Pulse::Low);
-                    self.fire(self.conj_pulse_to_fire())
-                }
-                ModuleType::Broadcaster => self.fire(pulse),
-                ModuleType::Other => VecDeque::new(),
+/// High and low pulses sent after pressing the button `total_presses` times.
+/// If all the modules get back to their original state the presses repeat, so
+/// the full cycles are counted once and only the remainder is simulated.
+fn count_pulses(circuit: &mut Circuit, total_presses: i64) -> (i64, i64) {
+    let mut high_pulses = 0;
+    let mut low_pulses = 0;
+
+    for i in 1..total_presses + 1 {
+        press(circuit, &mut high_pulses, &mut low_pulses);
+        if circuit.is_reset() {
+            let cycles = total_presses / i;
+            high_pulses *= cycles;
+            low_pulses *= cycles;
+            for _ in 0..total_presses % i {
+                press(circuit, &mut high_pulses, &mut low_pulses);
             }
+            break;
         }
     }
 
-    fn process(
-        module: &Module,
-        from: &String,
-        pulse: Pulse,
-    ) -> (Module, VecDeque<(String, Pulse)>) {
-        let mut m = module.clone();
-        let pulses = m.process(from, pulse);
-
-        (m, pulses)
-    }
-
-    fn press_button(data: &mut HashMap<String, Module>) -> (i64, i64) {
-        let mut high_pulses = 0;
-        let mut low_pulses = 1;
-        let mut next_pulses = VecDeque::new();
-
-        next_pulses.push_front((
-            String::from("button"),
-            String::from("broadcaster"),
-            Pulse::Low,
-        ));
-
-        while let Some((from, next_name, pulse)) = next_pulses.pop_front() {
-            match data.get_mut(&next_name) {
-                Some(m) => {
-                    let more_pulses = m.process(&from, pulse);
-                    // data.insert(next_name.clone(), m.clone());
-                    // for p in &more_pulses {
-                    //     println!("{} -{:?}-> {}", m.get_name(), p.1, p.0);
-                    // }
+    (high_pulses, low_pulses)
+}
 
-                    for p in more_pulses {
-                        if p.1 == Pulse::Low {
-                            low_pulses += 1;
-                        } else {
-                            high_pulses += 1;
-                        }
-                        next_pulses.push_back((next_name.clone(), p.0, p.1));
-                    }
-                }
-                None => {
-                    // println!("{}", next_name);
-                }
-            }
+fn press(circuit: &mut Circuit, high_pulses: &mut i64, low_pulses: &mut i64) {
+    for event in circuit.press() {
+        match event.pulse {
+            Pulse::High => *high_pulses += 1,
+            Pulse::Low => *low_pulses += 1,
         }
-
-        (high_pulses, low_pulses)
     }
+}
 
-    fn press_button_p2(
-        data: &mut HashMap<String, Module>,
-        pres: &HashSet<&String>,
-    ) -> Option<String> {
-        let mut next_pulses = VecDeque::new();
-
-        next_pulses.push_front((
-            String::from("button"),
-            String::from("broadcaster"),
-            Pulse::Low,
-        ));
-
-        while let Some((from, next_name, pulse)) = next_pulses.pop_front() {
-            match data.get_mut(&next_name) {
-                Some(m) => {
-                    if pulse == Pulse::Low && next_name == "rx" {
-                        return Some(from);
-                    }
-                    if pulse == Pulse::High && pres.contains(&from) && next_name.contains("hp") {
-                        return Some(from);
-                    }
-
-                    let more_pulses = m.process(&from, pulse);
-
-                    for p in more_pulses {
-                        next_pulses.push_back((next_name.clone(), p.0, p.1));
-                    }
-                }
-                None => {
-                    // println!("{}", next_name);
-                }
-            }
-        }
-
-        None
-    }
+/// Presses to wait for each high pulse into the last conjunction before giving
+/// up, the real inputs cycle every few thousand presses.
+const PRESS_LIMIT: u64 = 100_000;
+
+/// `rx` is fed by a single conjunction, so it gets a low pulse once all of the
+/// conjunction's inputs send a high pulse in the same press. Each input cycles
+/// on its own, so the answer is the first press where all of their cycles line
+/// up. The first two high pulses of an input give where its cycle starts and
+/// how long it is.
+fn presses_until_rx(circuit: &Circuit) -> Result<i64, String> {
+    let rx = circuit.index_of("rx").ok_or("no rx module")?;
+    let last = circuit
+        .feeder(rx)
+        .ok_or("rx isn't fed by a single conjunction")?;
+
+    let mut cycles = Vec::new();
+    for &from in circuit.inputs(last) {
+        let watch = Watch::new(last, Pulse::High).from(from);
+        let mut circuit = circuit.clone();
+        let first = circuit.press_until(&watch, PRESS_LIMIT);
+        let second = first.and_then(|_| circuit.press_until(&watch, PRESS_LIMIT));
+        let (Some(first), Some(second)) = (first, second) else {
+            return Err(format!(
+                "{} doesn't send a high pulse twice within {} presses",
+                circuit.name(from),
+                PRESS_LIMIT
+            ));
+        };
+        cycles.push((first as i64, (second - first) as i64));
+    }
+
+    math::first_common(cycles).ok_or_else(|| "the inputs of rx never line up".to_string())
+}
 
-    fn set_initial_state(data: &mut HashMap<String, Module>) {
-        let mut target_to_source: HashMap<String, HashSet<String>> = HashMap::new();
-        for (key, val) in data.iter() {
-            for t in &val.targets {
-                target_to_source
-                    .entry(t.clone())
-                    .or_insert(HashSet::new())
-                    .insert(key.clone());
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use std::fs;
 
-        for (target, val) in data {
-            match target_to_source.get(target) {
-                Some(sources) => {
-                    for s in sources {
-                        val.last_pulse_received.insert(s.to_string(), Pulse::Low);
-                    }
-                }
-                None => {}
-            }
-        }
-    }
+    use crate::circuit::Circuit;
 
-    fn get_input() -> HashMap<String, Module> {
-        let contents = fs::read_to_string("./problems/d20.txt").expect("");
-        contents
-            .lines()
-            .map(|l| l.split_once(" -> ").unwrap())
-            .map(|pair| {
-                (
-                    pair.0.trim().replace("%", "").replace("&", "").to_string(),
-                    Module::from_str(
-                        pair.0.trim(),
-                        pair.1
-                            .trim()
-                            .split(",")
-                            .map(|e| e.trim())
-                            .collect::<Vec<_>>(),
-                    ),
-                )
-            })
-            .collect::<HashMap<_, _>>()
-    }
+    use super::{count_pulses, press, presses_until_rx};
 
     #[test]
     fn p1() {
-        let mut data: HashMap<String, Module> = get_input();
-
-        // println!("{:?}", data);
-
-        set_initial_state(&mut data);
-        println!("{:?}", data);
-
-        let mut high_pulses = 0;
-        let mut low_pulses = 0;
-
-        let total_presses = 1000;
-        let mut presses_to_go = 0;
-        let mut presses_multiplier = 1;
-
-        for i in 1..total_presses + 1 {
-            let (h, l) = press_button(&mut data);
-            high_pulses += h;
-            low_pulses += l;
-            if data.values().all(|v| v.initial_state) {
-                if i < total_presses {
-                    presses_multiplier = total_presses / i;
-                    presses_to_go = total_presses % i;
-                }
-
-                println!("Original state after {} button presses", i);
-                break;
-            }
-        }
+        let contents = fs::read_to_string("./problems/d20.txt").expect("");
+        let mut circuit = Circuit::parse(&contents).unwrap();
 
-        high_pulses = high_pulses * presses_multiplier;
-        low_pulses = low_pulses * presses_multiplier;
+        let (high_pulses, low_pulses) = count_pulses(&mut circuit, 1000);
 
         println!(
             "Emitted Pulses:  high:{}, low:{}, total:{}",
@@ -306,117 +116,49 @@ mod tests {
             low_pulses,
             high_pulses * low_pulses
         );
-
-        println!("presses_to_go: {}", presses_to_go);
-    }
-
-    fn lcm_list(list: Vec<i64>) -> i64 // Restrict T to types that are primitive integers
-    {
-        list.iter().cloned().fold(1, |acc, x| lcm(acc, x))
     }
 
     #[test]
     fn p2() {
-        let mut data: HashMap<String, Module> = get_input();
-
-        set_initial_state(&mut data);
-
-        let last = data
-            .values()
-            .find(|m| m.targets.contains(&String::from("rx")))
-            .unwrap()
-            .clone();
-
-        println!("{}", last);
-
-        let mut pre_last: HashSet<&String> =
-            last.last_pulse_received.keys().collect::<HashSet<_>>();
-        println!("{:?}", pre_last);
-
-        let mut i = 1;
-        let mut founds = Vec::new();
-        loop {
-            match press_button_p2(&mut data, &pre_last) {
-                Some(f) => {
-                    println!("Found {} at {}", f, i);
-                    pre_last.remove(&f);
-                    founds.push(i);
-                    // break;
-                }
-                None => {}
-            }
-            if pre_last.is_empty() {
-                break;
-            }
-            i += 1;
-        }
+        let contents = fs::read_to_string("./problems/d20.txt").expect("");
+        let circuit = Circuit::parse(&contents).unwrap();
 
-        println!("{}", lcm_list(founds));
+        println!("{}", presses_until_rx(&circuit).unwrap());
     }
 
     #[test]
-    fn test_flip_flop() {
-        let mut rm = Module::from_str("%x", vec!["a1", "a2", "a3"]);
-        let pl = String::from("_");
-        match &rm.module_type {
-            &ModuleType::FlipFlop(state) => {
-                assert!(rm.initial_state);
-                assert_eq!(state, false)
-            }
-            _ => unreachable!(),
-        }
-        let ts = rm.process(&pl, Pulse::High);
-        assert!(ts.is_empty());
-        match &rm.module_type {
-            &ModuleType::FlipFlop(state) => {
-                assert!(rm.initial_state);
-                assert_eq!(state, false)
-            }
-            _ => unreachable!(),
-        }
+    fn sample() {
+        let contents = fs::read_to_string("./problems/d20_sample1.txt").expect("");
+        let mut circuit = Circuit::parse(&contents).unwrap();
+        assert_eq!(count_pulses(&mut circuit, 1000), (4000, 8000));
 
-        let ts = rm.process(&pl, Pulse::Low);
-
-        match &rm.module_type {
-            &ModuleType::FlipFlop(state) => {
-                assert_eq!(rm.initial_state, false);
-                assert_eq!(state, true)
-            }
-            _ => unreachable!(),
-        }
-
-        assert_eq!(ts.len(), 3);
+        let contents = fs::read_to_string("./problems/d20_sample2.txt").expect("");
+        let mut circuit = Circuit::parse(&contents).unwrap();
+        assert_eq!(count_pulses(&mut circuit, 1000), (2750, 4250));
+    }
 
-        let ts = rm.process(&pl, Pulse::Low);
-        match &rm.module_type {
-            &ModuleType::FlipFlop(state) => {
-                assert!(rm.initial_state);
-                assert_eq!(state, false)
+    #[test]
+    fn partial_cycle() {
+        let contents = fs::read_to_string("./problems/d20_sample2.txt").expect("");
+        let circuit = Circuit::parse(&contents).unwrap();
+
+        for total_presses in [1, 3, 5, 7, 1001] {
+            let mut slow = circuit.clone();
+            let (mut high_pulses, mut low_pulses) = (0, 0);
+            for _ in 0..total_presses {
+                press(&mut slow, &mut high_pulses, &mut low_pulses);
             }
-            _ => unreachable!(),
+            assert_eq!(
+                count_pulses(&mut circuit.clone(), total_presses),
+                (high_pulses, low_pulses)
+            );
         }
-        assert_eq!(ts.len(), 3);
     }
 
     #[test]
-    fn test_conj() {
-        let mut m = Module::from_str("&x", vec!["a1", "a2"]);
-        // let pl = String::from("_");
-        assert!(m.initial_state);
-        assert!(m.last_pulse_received.values().all(|v| *v == Pulse::Low));
-
-        // first, send low pulse
-        let ts = m.process(&String::from("a1"), Pulse::Low);
-        assert!(m.initial_state);
-        assert!(m.last_pulse_received.values().all(|v| *v == Pulse::Low));
-
-        assert!(ts.iter().all(|p| p.1 == Pulse::High));
-
-        // now send high pulse
-        let ts = m.process(&String::from("a1"), Pulse::High);
-        assert!(!m.initial_state);
-        assert!(!m.last_pulse_received.values().all(|v| *v == Pulse::Low));
-
-        assert!(ts.iter().all(|p| p.1 == Pulse::Low))
+    fn no_rx() {
+        let contents = fs::read_to_string("./problems/d20_sample1.txt").expect("");
+        let circuit = Circuit::parse(&contents).unwrap();
+        assert_eq!(presses_until_rx(&circuit), Err("no rx module".to_string()));
     }
 }
@@ -1,18 +1,119 @@
 #![allow(dead_code)]
 
+use crate::{
+    grid::{Grid, Tiled},
+    input::InputError,
+    search,
+    solution::{Answer, Solution},
+};
+use nalgebra::{RowVector3, Vector3};
+use std::{collections::HashSet, hash::Hash};
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Grid<char>;
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        contents.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        let start = input.find(&'S').unwrap();
+
+        count_steps(64, start, input).len().into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        (count_steps_repeated(input, 26501365).round() as i64).into()
+    }
+}
+
+/// Cells that can be walked on
+const PLOTS: [char; 3] = ['.', 'S', 'O'];
+
+fn count_steps(steps: i32, start: (i32, i32), grid: &Grid<char>) -> HashSet<(i32, i32)> {
+    walk(steps, start, |(x, y)| {
+        grid.orthogonal_neighbours(x, y)
+            .filter(|(_, _, c)| PLOTS.contains(c))
+            .map(|(x, y, _)| (x, y))
+    })
+}
+
+/// Same as `count_steps`, with the map repeating forever in every direction.
+fn count_steps_tiled(steps: i32, start: (i64, i64), tiled: Tiled<char>) -> HashSet<(i64, i64)> {
+    walk(steps, start, |(x, y)| {
+        tiled
+            .orthogonal_neighbours(x, y)
+            .filter(|(_, _, c)| PLOTS.contains(c))
+            .map(|(x, y, _)| (x, y))
+    })
+}
+
+/// Positions where a walk of exactly `steps` steps can end. Going back and
+/// forth wastes two steps, so those are the ones reached an even number of
+/// steps before the end.
+fn walk<P, I>(steps: i32, start: P, next: impl Fn(P) -> I) -> HashSet<P>
+where
+    P: Copy + Eq + Hash,
+    I: Iterator<Item = P>,
+{
+    search::bfs_within(start, |p| next(*p), steps as usize)
+        .into_iter()
+        .filter(|(_, d)| d % 2 == steps as usize % 2)
+        .map(|(p, _)| p)
+        .collect()
+}
+
+/// The reachable tiles grow quadratically with the number of times the walk
+/// crosses the map, so fit a quadratic on the first three crossings.
+fn count_steps_repeated(tile: &Grid<char>, steps: i32) -> f64 {
+    let size = tile.cols();
+    let (x, y) = tile.find(&'S').unwrap();
+    let start = (x as i64, y as i64);
+
+    let half = size / 2;
+    let a = count_steps_tiled(half, start, tile.tiled()).len();
+    let b = count_steps_tiled(half + size, start, tile.tiled()).len();
+    let c = count_steps_tiled(half + size * 2, start, tile.tiled()).len();
+
+    let mb = Vector3::new(a as f64, b as f64, c as f64);
+    let ma = nalgebra::Matrix3::from_rows(&[
+        RowVector3::new(0.0, 0.0, 1.0),
+        RowVector3::new(1.0, 1.0, 1.0),
+        RowVector3::new(4.0, 2.0, 1.0),
+    ]);
+    let decomp = ma.lu();
+    let x = decomp.solve(&mb).expect("Linear resolution failed.");
+
+    let n = (steps / size) as f64;
+    x[0] * n * n + x[1] * n + x[2]
+}
+
+fn mark(steps: i32, start: (i32, i32), grid1: &mut Grid<char>, grid: &Grid<char>) -> usize {
+    // let mut grid1 = grid.clone();
+    let answ = count_steps(steps, start, grid);
+    for (x, y) in &answ {
+        if grid1.get(*x, *y).unwrap() == &'X' {
+            grid1.set(*x, *y, 'O');
+        } else {
+            grid1.set(*x, *y, 'X');
+        }
+    }
+    answ.len()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::grid::Grid;
-    use nalgebra::{RowVector3, Vector3};
-    use std::{
-        collections::{HashSet, VecDeque},
-        fs,
-    };
+    use std::fs;
+
+    use super::{count_steps, count_steps_repeated};
 
     #[test]
     fn p1() {
         let contents = fs::read_to_string("./problems/d21.txt").expect("");
-        let grid = Grid::new(contents.lines().map(|l| l.chars().collect()).collect());
+        let grid: Grid<char> = contents.parse().unwrap();
 
         let start = grid.find(&'S').unwrap();
 
@@ -29,130 +130,11 @@ mod tests {
         println!("{:?}", answ.len());
     }
 
-    fn count_steps(steps: i32, start: (i32, i32), grid: &Grid<char>) -> HashSet<(i32, i32)> {
-        let mut possible_steps: VecDeque<(i32, i32, i32)> =
-            VecDeque::from([(start.0, start.1, steps)]);
-
-        let mut found: HashSet<(i32, i32)> = HashSet::new();
-
-        let mut answ = HashSet::new();
-
-        while let Some((x, y, steps_to_go)) = possible_steps.pop_front() {
-            if steps_to_go % 2 == 0 {
-                answ.insert((x, y));
-            }
-            if steps_to_go == 0 {
-                continue;
-            }
-
-            let directions = get_empty_positions(grid, x, y);
-
-            for (col, row) in &directions {
-                if found.contains(&(*col, *row)) {
-                    continue;
-                }
-                found.insert((*col, *row));
-                possible_steps.push_back((*col, *row, steps_to_go - 1));
-            }
-        }
-        answ
-    }
-
     #[test]
     fn p2() {
-        let grid = get_expanded_grid((9, 9 as usize));
-
-        let center = (grid.cols() / 2) as i32;
-        assert_eq!(grid.get(center, center).unwrap(), &'S');
-
-        let a = count_steps(65, (center, center), &grid).len();
-        let b = count_steps(65 + 131, (center, center), &grid).len();
-        let c = count_steps(65 + 131 * 2, (center, center), &grid).len();
-
-        println!("a={}, b={}, c={}", a, b, c);
-
-        let mb = Vector3::new(a as f64, b as f64, c as f64);
-        let ma = nalgebra::Matrix3::from_rows(&[
-            RowVector3::new(0.0, 0.0, 1.0),
-            RowVector3::new(1.0, 1.0, 1.0),
-            RowVector3::new(4.0, 2.0, 1.0),
-        ]);
-        let decomp = ma.lu();
-        let x = decomp.solve(&mb).expect("Linear resolution failed.");
-        println!("{:?}", x);
-
-        let n = (26501365 / get_expanded_grid((1, 1 as usize)).cols()) as f64;
-        let res = x[0] * n * n + x[1] * n + x[2];
-
-        println!("res: {}", res);
-    }
-
-    fn mark(steps: i32, start: (i32, i32), grid1: &mut Grid<char>, grid: &Grid<char>) -> usize {
-        // let mut grid1 = grid.clone();
-        let answ = count_steps(steps, start, &grid);
-        for (x, y) in &answ {
-            if grid1.get(*x, *y).unwrap() == &'X' {
-                grid1.set(*x, *y, 'O');
-            } else {
-                grid1.set(*x, *y, 'X');
-            }
-        }
-        answ.len()
-    }
-
-    fn get_expanded_grid(expansions: (i32, usize)) -> Grid<char> {
         let contents = fs::read_to_string("./problems/d21.txt").expect("");
+        let res = count_steps_repeated(&contents.parse().unwrap(), 26501365);
 
-        let mut rows = Vec::new();
-
-        for _ in 0..expansions.0 {
-            for line in contents.lines() {
-                let expanded_cols = vec![line; expansions.1].join("");
-                rows.push(expanded_cols.chars().collect::<Vec<_>>())
-            }
-        }
-
-        Grid::new(rows)
-    }
-
-    fn get_empty_positions(grid: &Grid<char>, x: i32, y: i32) -> VecDeque<(i32, i32)> {
-        let directions: VecDeque<(i32, i32)> = grid
-            .get_xy_directions_with_match(x, y, &HashSet::from(['.', 'S', 'O']))
-            .iter()
-            .map(|(a, b, _)| (*a, *b))
-            .collect();
-        directions
-    }
-
-    fn normalize_coords(grid: &Grid<char>, mut col: i32, mut row: i32) -> (i32, i32) {
-        let rows = grid.rows();
-        let cols = grid.cols();
-
-        // println!("{},{}", col, row);
-
-        if col < 0 {
-            col = cols - (col.abs() % (cols));
-        }
-
-        if row < 0 {
-            row = rows - (row.abs() % (rows));
-        }
-
-        if col >= cols {
-            col = col % (cols);
-        }
-
-        if row >= rows {
-            row = row % (rows);
-        }
-        (col, row)
-    }
-
-    fn get_with_repeat(grid: &Grid<char>, mut col: i32, mut row: i32) -> Option<&char> {
-        (col, row) = normalize_coords(grid, col, row);
-        let res = grid.get(col, row);
-
-        assert!(res.is_some(), "element should be present: {}, {}", col, row);
-        res
+        println!("res: {}", res);
     }
 }
@@ -1,240 +1,316 @@
-#![allow(dead_code)]
+use std::{ops::Range, str::FromStr};
 
-#[cfg(test)]
-mod tests {
-    use nalgebra::{Matrix6, RowVector6, Vector3, Vector6};
-    use std::{fs, str::FromStr};
-
-    #[derive(Debug)]
-    struct Coord {
-        x: f64,
-        y: f64,
-        z: f64,
-    }
-
-    #[derive(Debug)]
-    struct Position<T: Copy + Clone + PartialEq> {
-        x: T,
-        y: T,
-        z: T,
-        vx: T,
-        vy: T,
-        vz: T,
-    }
-
-    impl<
-            T: Copy
-                + Clone
-                + core::fmt::Debug
-                + PartialEq
-                + FromStr
-                + std::ops::Neg<Output = T>
-                + std::ops::Sub<Output = T>
-                + std::ops::Mul<Output = T>
-                + std::ops::Div<Output = T>
-                + std::ops::Add<Output = T>,
-        > Position<T>
+use crate::{
+    input::InputError,
+    linear::{self, Rational},
+    parsing::{coords, lines, pair},
+    solution::{Answer, Solution},
+};
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = Vec<Position<i64>>;
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        lines(contents, Position::parse)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        let range = 200000000000000.0..400000000000000.0 + 0.0000000001;
+        count_collisions(input, range).into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        rock_position(input).map_or_else(Answer::from, Answer::from)
+    }
+}
+
+#[derive(Debug)]
+struct Coord {
+    x: f64,
+    y: f64,
+}
+
+#[derive(Debug)]
+pub struct Position<T: Copy + Clone + PartialEq> {
+    x: T,
+    y: T,
+    z: T,
+    vx: T,
+    vy: T,
+    vz: T,
+}
+
+impl<
+        T: Copy
+            + Clone
+            + core::fmt::Debug
+            + PartialEq
+            + FromStr
+            + std::ops::Neg<Output = T>
+            + std::ops::Sub<Output = T>
+            + std::ops::Mul<Output = T>
+            + std::ops::Div<Output = T>
+            + std::ops::Add<Output = T>,
+    > Position<T>
+{
+    fn parse(str: &str) -> Result<Self, InputError> {
+        let (left, right) = pair(str, "@")?;
+        let [x, y, z] = coords(left)?;
+        let [vx, vy, vz] = coords(right)?;
+
+        Ok(Position {
+            x,
+            y,
+            z,
+            vx,
+            vy,
+            vz,
+        })
+    }
+
+    fn to_line(&self) -> (T, T) {
+        let m = self.vy / self.vx;
+        let b = -m * self.x + self.y;
+        (m, b)
+    }
+
+    fn get_time_for_other_x(&self, other_x: T) -> T {
+        (other_x - self.x) / self.vx
+    }
+
+    fn intersection(&self, other: &Self) -> Coord
+    where
+        f64: From<T>,
     {
-        fn parse(str: &str) -> Self
-        where
-            <T as FromStr>::Err: core::fmt::Debug,
-        {
-            let (left, right) = str.split_once(" @ ").unwrap();
-
-            let left = left
-                .trim()
-                .split(",")
-                .map(|e| e.trim())
-                .map(|e| T::from_str(e).unwrap())
-                .collect::<Vec<_>>();
-
-            let right = right
-                .trim()
-                .split(",")
-                .map(|e| e.trim())
-                .map(|e| T::from_str(e).unwrap())
-                .collect::<Vec<_>>();
-
-            Position {
-                x: left[0],
-                y: left[1],
-                z: left[2],
-                vx: right[0],
-                vy: right[1],
-                vz: right[2],
-            }
+        // ax+by+cz = 0
+        let (m1, lb1) = self.to_line();
+        let (m2, lb2) = other.to_line();
+
+        let a1: f64 = m1.into();
+        let a2: f64 = m2.into();
+        let b1 = -1.0;
+        let b2 = -1.0;
+        let c1: f64 = lb1.into();
+        let c2: f64 = lb2.into();
+
+        let x = (b1 * c2 - b2 * c1) / (a1 * b2 - a2 * b1);
+        let y = (a2 * c1 - a1 * c2) / (a1 * b2 - a2 * b1);
+
+        Coord { x, y }
+    }
+}
+
+impl Position<i64> {
+    fn to_f64(&self) -> Position<f64> {
+        Position {
+            x: self.x as f64,
+            y: self.y as f64,
+            z: self.z as f64,
+            vx: self.vx as f64,
+            vy: self.vy as f64,
+            vz: self.vz as f64,
         }
+    }
+}
 
-        fn clone_after_time(&self, time: T) -> Position<T> {
-            Self {
-                x: self.x + time * self.vx,
-                y: self.y + time * self.vy,
-                z: self.z + time * self.vz,
-                vx: self.vx,
-                vy: self.vy,
-                vz: self.vz,
+/// Number of pairs of hailstones whose paths cross inside the test area, ignoring z
+fn count_collisions(hailstones: &[Position<i64>], range: Range<f64>) -> i32 {
+    let data = hailstones.iter().map(|h| h.to_f64()).collect::<Vec<_>>();
+
+    let mut collisions = 0;
+    for (i1, p1) in data.iter().enumerate() {
+        for i2 in i1..data.len() {
+            if i1 != i2 {
+                let p2 = data.get(i2).unwrap();
+                let inter = p1.intersection(p2);
+                if range.contains(&inter.x)
+                    && range.contains(&inter.y)
+                    && p1.get_time_for_other_x(inter.x) > 0.0
+                    && p2.get_time_for_other_x(inter.x) > 0.0
+                {
+                    collisions += 1;
+                }
             }
         }
+    }
+    collisions
+}
 
-        fn to_line(&self) -> (T, T) {
-            let m = self.vy / self.vx;
-            let b = -m * self.x + self.y;
-            (m, b)
-        }
+type Vec3 = [i128; 3];
 
-        fn get_time_for_other_x(&self, other_x: T) -> T {
-            (other_x - self.x) / self.vx
-        }
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
 
-        fn intersection(&self, other: &Self) -> Coord
-        where
-            f64: From<T>,
-        {
-            // ax+by+cz = 0
-            let (m1, lb1) = self.to_line();
-            let (m2, lb2) = other.to_line();
-
-            let a1: f64 = m1.into();
-            let a2: f64 = m2.into();
-            let b1 = -1.0;
-            let b2 = -1.0;
-            let c1: f64 = lb1.into();
-            let c2: f64 = lb2.into();
-
-            let x = (b1 * c2 - b2 * c1) / (a1 * b2 - a2 * b1);
-            let y = (a2 * c1 - a1 * c2) / (a1 * b2 - a2 * b1);
-
-            Coord { x, y, z: 0.0 }
-        }
+fn dot(a: Vec3, b: Vec3) -> i128 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+impl Position<i64> {
+    fn pos(&self) -> Vec3 {
+        [self.x as i128, self.y as i128, self.z as i128]
     }
 
-    #[test]
-    fn p1() {
-        let contents = fs::read_to_string("./problems/d24.txt").expect("");
+    fn vel(&self) -> Vec3 {
+        [self.vx as i128, self.vy as i128, self.vz as i128]
+    }
 
-        let range = 7.0..27.0 + 0.001;
-        // let range = 200000000000000.0..400000000000000.0 + 0.0000000001;
+    /// Whether a rock thrown from `pos` at `vel` hits this hailstone, now or
+    /// later.
+    fn hit_by(&self, pos: Vec3, vel: Vec3) -> bool {
+        // pos + t * vel == self.pos + t * self.vel, for some t >= 0
+        let d = sub(pos, self.pos());
+        let w = sub(self.vel(), vel);
+        cross(d, w) == [0, 0, 0] && dot(d, w) >= 0 && (w != [0, 0, 0] || d == [0, 0, 0])
+    }
+}
 
-        let data = contents
-            .lines()
-            .map(|e| Position::<f64>::parse(e))
-            .collect::<Vec<_>>();
-
-        let mut collisions = 0;
-        for (i1, p1) in data.iter().enumerate() {
-            for i2 in i1..data.len() {
-                if i1 != i2 {
-                    let p2 = data.get(i2).unwrap();
-                    let inter = p1.intersection(p2);
-                    println!("{:?}", inter);
-                    if range.contains(&inter.x)
-                        && range.contains(&inter.y)
-                        && p1.get_time_for_other_x(inter.x) > 0.0
-                        && p2.get_time_for_other_x(inter.x) > 0.0
-                    {
-                        collisions += 1;
-                    }
+/// Position and velocity of the rock that hits every hailstone. Seen from the
+/// first hailstone, the rock goes through the origin, so it hits any other
+/// hailstone where that one crosses the plane holding the origin and a third
+/// hailstone's path. Two such hits pin down the rock's path exactly, unless
+/// the numbers get too large or the planes are parallel, in which case the
+/// next pair of hailstones is tried.
+fn throw_rock(data: &[Position<i64>]) -> Option<(Vec3, Vec3)> {
+    let (first, rest) = data.split_first()?;
+    let (p0, v0) = (first.pos(), first.vel());
+    let relative = |h: &Position<i64>| (sub(h.pos(), p0), sub(h.vel(), v0));
+
+    // When `h` crosses the plane of the origin and `other`, seen from the first
+    let hit_time = |h: &Position<i64>, other: &Position<i64>| {
+        let (p, v) = relative(h);
+        let (op, ov) = relative(other);
+        let normal = cross(op, ov);
+        let den = dot(v, normal);
+        (den != 0).then(|| Rational::new(-dot(p, normal), den))
+    };
+
+    let rock = |hi: &Position<i64>, hj: &Position<i64>| {
+        let (ti, tj) = (hit_time(hi, hj)?, hit_time(hj, hi)?);
+
+        // rock + t * rock_vel is where the hailstone is at that time
+        let mut matrix = Vec::new();
+        let mut rhs = Vec::new();
+        for (h, t) in [(hi, ti), (hj, tj)] {
+            let (p, v) = relative(h);
+            for k in 0..3 {
+                let mut row = vec![Rational::ZERO; 6];
+                row[k] = Rational::ONE;
+                row[k + 3] = t;
+                matrix.push(row);
+                let moved = t.checked_mul(Rational::from(v[k]))?;
+                rhs.push(Rational::from(p[k]).checked_add(moved)?);
+            }
+        }
+
+        let x = linear::solve(&matrix, &rhs).ok()?;
+        let x = x
+            .iter()
+            .map(|v| v.to_integer())
+            .collect::<Option<Vec<_>>>()?;
+        let mut pos = [0; 3];
+        let mut vel = [0; 3];
+        for k in 0..3 {
+            pos[k] = x[k].checked_add(p0[k])?;
+            vel[k] = x[k + 3].checked_add(v0[k])?;
+        }
+        Some((pos, vel))
+    };
+
+    for (i, hi) in rest.iter().enumerate() {
+        for hj in &rest[i + 1..] {
+            if let Some((pos, vel)) = rock(hi, hj) {
+                if data.iter().all(|h| h.hit_by(pos, vel)) {
+                    return Some((pos, vel));
                 }
             }
         }
+    }
+    None
+}
 
-        println!("collisions: {}", collisions);
+/// Sum of the coordinates the rock that hits every hailstone is thrown from.
+fn rock_position(data: &[Position<i64>]) -> Result<i128, String> {
+    if data.is_empty() {
+        return Err("no hailstones".to_string());
     }
+    let (pos, _) = throw_rock(data).ok_or("no rock hits every hailstone")?;
+    Ok(pos.iter().sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::solution::Solution;
+
+    use super::{count_collisions, rock_position, throw_rock, Day24, Position};
 
     #[test]
-    fn p2() {
+    fn p1() {
         let contents = fs::read_to_string("./problems/d24.txt").expect("");
 
-        // // let range = 7.0..27.0 + 0.001;
+        let range = 7.0..27.0 + 0.001;
         // let range = 200000000000000.0..400000000000000.0 + 0.0000000001;
 
-        let data = contents
-            .lines()
-            .map(|e| Position::<i64>::parse(e))
-            .collect::<Vec<_>>();
-
-        let mut ma = Vec::new();
-        let mut mb = Vec::new();
-
-        for i in 0..2 {
-            let j = i + 1;
-
-            let hi = data.get(i).unwrap();
-            let hj = data.get(j).unwrap();
-
-            let a = Vector3::new(hi.x, hi.y, hi.z).cross(&Vector3::new(hi.vx, hi.vy, hi.vz));
-            let b = Vector3::new(hj.x, hj.y, hj.z).cross(&Vector3::new(hj.vx, hj.vy, hj.vz));
-
-            mb.append(
-                &mut a
-                    .iter()
-                    .zip(b.iter())
-                    .map(|(ia, ib)| (ia - ib) as f64)
-                    .rev()
-                    .collect::<Vec<_>>(),
-            );
-
-            let x = hi.vy - hj.vy;
-            let y = -(hi.vx - hj.vx);
-            let z = 0;
-            let vx = -(hi.y - hj.y);
-            let vy = hi.x - hj.x;
-            let vz = 0;
-            ma.push(RowVector6::from_vec(
-                [x, y, z, vx, vy, vz].iter().map(|e| *e as f64).collect(),
-            ));
-
-            let x = -(hi.vz - hj.vz);
-            let y = 0;
-            let z = hi.vx - hj.vx;
-            let vx = hi.z - hj.z;
-            let vy = 0;
-            let vz = -(hi.x - hj.x);
-
-            ma.push(RowVector6::from_vec(
-                [x, y, z, vx, vy, vz].iter().map(|e| *e as f64).collect(),
-            ));
-
-            let x = 0;
-            let y = hi.vz - hj.vz;
-            let z = -(hi.vy - hj.vy);
-            let vx = 0;
-            let vy = -(hi.z - hj.z);
-            let vz = hi.y - hj.y;
-
-            ma.push(RowVector6::from_vec(
-                [x, y, z, vx, vy, vz].iter().map(|e| *e as f64).collect(),
-            ));
-        }
+        let hailstones = Day24::parse(&contents).unwrap();
+        println!("collisions: {}", count_collisions(&hailstones, range));
+    }
 
-        let ma = Matrix6::from_rows(&ma);
-        let mb = Vector6::from_vec(mb);
+    #[test]
+    fn p2() {
+        let contents = fs::read_to_string("./problems/d24.txt").expect("");
 
-        let decomp = ma.lu();
-        let x = decomp.solve(&mb).expect("Linear resolution failed.");
+        println!(
+            "{}",
+            rock_position(&Day24::parse(&contents).unwrap()).unwrap()
+        );
+    }
 
-        let res = x[0] + x[1] + x[2];
-        let res = res.round();
-        println!("{}", res);
+    #[test]
+    fn throw_rock_test() {
+        let contents = fs::read_to_string("./problems/d24_sample1.txt").expect("");
+        let hailstones = Day24::parse(&contents).unwrap();
+
+        assert_eq!(throw_rock(&hailstones), Some(([24, 13, 10], [-3, 1, 2])));
+        assert_eq!(rock_position(&hailstones), Ok(47));
+        assert_eq!(throw_rock(&[]), None);
+        assert_eq!(rock_position(&[]), Err("no hailstones".to_string()));
+
+        let stone = Position::parse("24, 13, 10 @ -3, 1, 2").unwrap();
+        assert!(stone.hit_by([24, 13, 10], [-3, 1, 2]));
+        assert!(!stone.hit_by([24, 13, 11], [-3, 1, 2]));
+        // Only a hailstone that's still ahead can be hit
+        let behind = Position::parse("21, 14, 12 @ 0, 0, 0").unwrap();
+        assert!(!behind.hit_by([24, 13, 10], [3, -1, -2]));
+        // The sample rock meets the first hailstone at 9, 18, 20 after 5ns,
+        // thrown from there they'd have met 5ns ago
+        let first = &hailstones[0];
+        assert!(first.hit_by([24, 13, 10], [-3, 1, 2]));
+        assert!(!first.hit_by([9, 18, 20], [-3, 1, 2]));
     }
 
     #[test]
     fn test_speed() {
-        let p = Position::parse("20, 19, 15 @ 1, -5, -3");
-
-        println!("{:?}", p);
-
-        println!("{:?}", p.clone_after_time(1.0));
+        let p = Position::<f64>::parse("20, 19, 15 @ 1, -5, -3").unwrap();
+        assert_eq!(p.get_time_for_other_x(23.0), 3.0);
+        assert_eq!(p.get_time_for_other_x(18.0), -2.0);
     }
 
     #[test]
     fn test_line() {
-        let p = Position::parse("6, 4, 15 @ 1, -5, -3");
-
-        println!("{:?}", p);
-
-        println!("{:?}", p.clone_after_time(1.0));
-
-        p.to_line();
+        let p = Position::<f64>::parse("6, 4, 15 @ 1, -5, -3").unwrap();
+        // y = -5x + 34
+        assert_eq!(p.to_line(), (-5.0, 34.0));
     }
 }
@@ -1 +1,34 @@
-// day25 was done in Python
+use crate::{
+    dot::Dot,
+    graph::Graph,
+    input::InputError,
+    solution::{Answer, Solution},
+};
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = Graph<String>;
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        Graph::parse_adjacency(contents)
+    }
+
+    /// Cutting the three wires that hold the two groups together, the sizes
+    /// of both groups multiplied.
+    fn part1(input: &Self::Input) -> Answer {
+        let cut = input.min_cut().unwrap();
+        let (a, b) = cut.sizes(input.len());
+        (a * b).into()
+    }
+
+    /// The last day only has one puzzle.
+    fn part2(_input: &Self::Input) -> Answer {
+        "Merry Christmas".into()
+    }
+
+    /// The three wires to cut stand out between the two tangles.
+    fn dot(input: &Self::Input) -> Option<Dot> {
+        Some(input.to_dot())
+    }
+}
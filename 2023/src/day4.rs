@@ -1,3 +1,59 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    input::InputError,
+    parsing::{lines, number, pair},
+    solution::{Answer, Solution},
+};
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    /// Number of winning numbers on each card
+    type Input = Vec<usize>;
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        lines(contents, |l| {
+            let (winners, tickets) = parse_card(l)?;
+            Ok(winners.intersection(&tickets).count())
+        })
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        let base: i32 = 2;
+        input
+            .iter()
+            .filter(|count| **count > 0)
+            .map(|count| base.pow(*count as u32 - 1))
+            .sum::<i32>()
+            .into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        let mut copies = HashMap::new();
+
+        for (i, count) in input.iter().enumerate() {
+            copies.insert(i, *copies.get(&i).unwrap_or(&0) + 1);
+
+            let copies_len = *copies.get(&i).unwrap_or(&1);
+
+            for c in i + 1..i + 1 + count {
+                copies.insert(c, copies.get(&c).unwrap_or(&0) + copies_len);
+            }
+        }
+
+        copies.values().sum::<usize>().into()
+    }
+}
+
+/// The winning numbers and the numbers we have on a card.
+fn parse_card(line: &str) -> Result<(HashSet<u32>, HashSet<u32>), InputError> {
+    let (_, numbers) = pair(line, ":")?;
+    let (winners, tickets) = pair(numbers, "|")?;
+    let numbers = |s: &str| s.split_whitespace().map(number).collect::<Result<_, _>>();
+    Ok((numbers(winners)?, numbers(tickets)?))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -6,6 +62,10 @@ mod tests {
         fs,
     };
 
+    use crate::solution::Solution;
+
+    use super::Day4;
+
     #[test]
     fn p1() {
         let contents = fs::read_to_string("./problems/d4.txt").expect("");
@@ -40,9 +100,9 @@ mod tests {
         let mut sum = 0;
 
         for (winners, tickets) in tickets {
-            let winners_hash = winners.iter().map(|t| *t).collect::<HashSet<_>>();
+            let winners_hash = winners.iter().copied().collect::<HashSet<_>>();
 
-            let tickets_hash = tickets.iter().map(|t| *t).collect::<HashSet<_>>();
+            let tickets_hash = tickets.iter().copied().collect::<HashSet<_>>();
 
             let winner_tickets = winners_hash.intersection(&tickets_hash).collect::<Vec<_>>();
 
@@ -89,9 +149,9 @@ mod tests {
         let mut copies = HashMap::new();
 
         for (i, (winners, tickets)) in tickets.iter().enumerate() {
-            let winners_hash = winners.iter().map(|t| *t).collect::<HashSet<_>>();
+            let winners_hash = winners.iter().copied().collect::<HashSet<_>>();
 
-            let tickets_hash = tickets.iter().map(|t| *t).collect::<HashSet<_>>();
+            let tickets_hash = tickets.iter().copied().collect::<HashSet<_>>();
 
             let winner_tickets = winners_hash.intersection(&tickets_hash).collect::<Vec<_>>();
 
@@ -108,11 +168,20 @@ mod tests {
             }
         }
 
-        let r: usize = copies
-            .values()
-            .map(|n| n.clone())
-            .reduce(|a, b| a + b)
-            .unwrap();
+        let r: usize = copies.values().copied().reduce(|a, b| a + b).unwrap();
         println!("Res: {}", r);
     }
+
+    #[test]
+    fn test_bad_input() {
+        let err = |contents| Day4::parse(contents).unwrap_err().to_string();
+        assert_eq!(
+            err("Card 1: 41 48 | 83 86\nCard 2: 13 x2 | 61"),
+            "line 2: not a number: `x2`"
+        );
+        assert_eq!(
+            err("Card 1: 41 48 83 86"),
+            "line 1: expected `|` in `41 48 83 86`"
+        );
+    }
 }
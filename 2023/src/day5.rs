@@ -1,136 +1,43 @@
 #![allow(dead_code)]
 use std::cmp::Ordering;
 
-#[derive(Debug)]
-struct RangeExt {
-    start: i32,
-    end: i32,
-    inclusive_end: bool,
-}
-
-impl RangeExt {
-    fn new(start: i32, end: i32) -> Self {
-        assert!(start < end);
-        Self {
-            start,
-            end,
-            inclusive_end: false,
-        }
-    }
-    fn intersection(&self, other: &Self) -> Option<Self> {
-        if self.end <= other.start || self.start >= other.end {
-            return None;
-        }
-        Some(Self::new(
-            self.start.max(other.start),
-            self.end.min(other.end),
-        ))
-    }
-
-    fn contains(&self, other: &Self) -> bool {
-        return self.start <= other.start && self.end >= other.end;
-    }
-
-    fn ldiff(&self, other: &Self) -> Option<Self> {
-        if self.start > other.start {
-            return None;
-        }
-        Some(Self::new(self.start, other.start))
-    }
-
-    fn rdiff(&self, other: &Self) -> Option<Self> {
-        if self.start < other.start {
-            return None;
-        }
-        Some(Self::new(other.end, self.end))
-    }
-
-    fn partition(&self, other: &Self) -> (Option<RangeExt>, Option<RangeExt>, Option<RangeExt>) {
-        (
-            self.ldiff(other),
-            self.intersection(other),
-            self.rdiff(other),
-        )
-    }
-}
-
-#[cfg(test)]
-mod range_tests {
-    use super::RangeExt;
-
-    #[test]
-    fn test_intersection() {
-        let r1 = RangeExt::new(5, 15);
-        let r2 = RangeExt::new(0, 10);
-
-        let maybe_diff = r1.intersection(&r2);
-        assert!(maybe_diff.is_some());
-        let diff = maybe_diff.unwrap();
-
-        println!("start={}, end={}", diff.start, diff.end)
-    }
-
-    #[test]
-    fn test_ldiff() {
-        let r1 = RangeExt::new(5, 15);
-        let r2 = RangeExt::new(0, 10);
+use crate::{
+    input::InputError,
+    interval::{Interval, IntervalSet},
+    parsing::{blocks, lines, number},
+    range_map::RangeMap,
+    solution::{Answer, Solution},
+};
 
-        let maybe_diff = r2.ldiff(&r1);
-        assert!(maybe_diff.is_some());
-        let diff = maybe_diff.unwrap();
-        println!("start={}, end={}", diff.start, diff.end)
-    }
+pub struct Day5;
 
-    #[test]
-    fn test_rdiff() {
-        let r1 = RangeExt::new(5, 15);
-        let r2 = RangeExt::new(0, 10);
+impl Solution for Day5 {
+    type Input = Input;
 
-        let maybe_diff = r1.rdiff(&r2);
-        assert!(maybe_diff.is_some());
-        let diff = maybe_diff.unwrap();
-        println!("start={}, end={}", diff.start, diff.end)
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        Input::parse(contents)
     }
 
-    #[test]
-    fn test_partition() {
-        let r1 = RangeExt::new(5, 15);
-        let r2 = RangeExt::new(0, 10);
-
-        let p1 = r1.partition(&r2);
-        let p2 = r2.partition(&r1);
-        println!("{:?}", p1);
-        println!("{:?}", p2);
-
-        assert!(p1.0.is_none());
-        assert!(p1.1.is_some());
-        assert!(p1.2.is_some());
-
-        assert!(p2.0.is_some());
-        assert!(p2.1.is_some());
-        assert!(p2.2.is_none());
+    fn part1(input: &Self::Input) -> Answer {
+        let seed_to_location = input.seed_to_location();
+        input
+            .seeds
+            .iter()
+            .map(|seed| seed_to_location.get(*seed))
+            .min()
+            .map_or_else(|| "no seeds".into(), Answer::from)
     }
 
-    #[test]
-    fn test_partition2() {
-        let r1 = RangeExt::new(5, 10);
-        let r2 = RangeExt::new(10, 15);
-
-        let p1 = r1.partition(&r2);
-
-        assert!(p1.0.is_some());
-        assert!(p1.1.is_none());
-        assert!(p1.2.is_none());
-
-        let p1 = r2.partition(&r1);
-
-        assert!(p1.0.is_none());
-        assert!(p1.1.is_none());
-        assert!(p1.2.is_some());
+    fn part2(input: &Self::Input) -> Answer {
+        let seed_to_location = input.seed_to_location();
+        seed_to_location
+            .map_set(&input.seeds_to_ranges())
+            .min()
+            .map_or_else(|| "no seed ranges".into(), Answer::from)
     }
 }
 
-#[derive(Eq)]
+#[derive(Debug, Eq)]
 pub struct MapDescription {
     dest_start: usize,
     source_start: usize,
@@ -155,16 +62,6 @@ impl Ord for MapDescription {
     }
 }
 
-#[derive(Debug)]
-enum RangeType {
-    Same,
-    SameR,
-    Range((usize, usize)),
-    Range2((usize, usize), (usize, usize)),
-    Range2R((usize, usize), (usize, usize)),
-    Range3((usize, usize), (usize, usize), (usize, usize)),
-}
-
 impl MapDescription {
     fn new(dest_start: usize, source_start: usize, range_len: usize) -> Self {
         Self {
@@ -174,232 +71,91 @@ impl MapDescription {
         }
     }
 
-    fn map_src_to_dest_range(&self, rstart: usize, rlen: usize) -> RangeType {
-        let rend = rstart + rlen;
-        let selfend = self.source_start + self.range_len;
-
-        // case 1: s1 e1 [s2 e2]
-        if rend < self.source_start {
-            return RangeType::Same;
-        }
-
-        // case 2: [s2 e2]  s1 e1
-        if rstart >= selfend {
-            return RangeType::SameR;
-        }
-
-        // case 3: s1 [s2 e1 e2]
-        if rstart < self.source_start && rend <= selfend {
-            return RangeType::Range2(
-                (rstart, self.source_start - rstart),
-                (
-                    self.dest_start,
-                    if rend == self.source_start {
-                        1
-                    } else {
-                        rend - self.source_start
-                    },
-                ),
-            );
-        }
-
-        // case 4:  [s2 s1 e1 e2]
-        if rstart >= self.source_start && rend <= selfend {
-            let s1_len = rstart - self.source_start;
-
-            let s1 = self.dest_start + s1_len;
-            let e1 = rlen;
-
-            return RangeType::Range((s1, e1));
-        }
-
-        // case 5:  [s2 s1 e2] e1
-        if rstart >= self.source_start && rstart < selfend && rend > selfend {
-            let s1_len = rstart - self.source_start;
-
-            let s1 = self.dest_start + s1_len;
-            let e1 = if self.range_len == s1_len {
-                1
-            } else {
-                self.range_len - s1_len
-            };
-
-            return RangeType::Range2R((s1, e1), (self.source_start + self.range_len, rlen - e1));
-        }
-
-        // case 6: s1 [s2 e2] e1
-        if rstart < self.source_start && rend > selfend {
-            let l1 = self.source_start - rstart;
-            let l2 = self.range_len;
-            let l3 = rlen - (l2 + l1);
-            return RangeType::Range3(
-                (rstart, l1),
-                (self.dest_start, l2),
-                (rstart + l2 + l1 + 1, l3),
-            );
-        }
-
-        unreachable!()
+    fn source(&self) -> Interval<usize> {
+        Interval::with_len(self.source_start, self.range_len)
     }
 }
 
 #[cfg(test)]
 mod tests_map_descr {
-    use super::MapDescription;
+    use crate::interval::{Interval, IntervalSet};
 
-    #[test]
-    fn case1() {
-        let map = MapDescription::new(52, 50, 48);
-        match map.map_src_to_dest_range(10, 5) {
-            super::RangeType::Same => {}
-            _ => panic!(""),
-        }
-    }
-
-    #[test]
-    fn case2() {
-        let map = MapDescription::new(52, 50, 2);
-        match map.map_src_to_dest_range(54, 5) {
-            super::RangeType::SameR => {}
-            r => panic!("{:?}", r),
-        }
-    }
-
-    #[test]
-    fn case3() {
-        let map = MapDescription::new(100, 50, 5);
-        match map.map_src_to_dest_range(49, 3) {
-            super::RangeType::Range2(r1, r2) => {
-                assert_eq!(r1.0, 49);
-                assert_eq!(r1.1, 1);
-
-                assert_eq!(r2.0, 100);
-                assert_eq!(r2.1, 2);
-            }
-            r => panic!("{:?}", r),
-        }
-    }
-
-    #[test]
-    fn case3_extra1() {
-        let map = MapDescription::new(100, 50, 5);
-        match map.map_src_to_dest_range(45, 5) {
-            super::RangeType::Range2(r1, r2) => {
-                assert_eq!(r1.0, 45);
-                assert_eq!(r1.1, 5);
-
-                assert_eq!(r2.0, 100);
-                assert_eq!(r2.1, 1);
-            }
-            r => panic!("{:?}", r),
-        }
-    }
-
-    #[test]
-    fn case4() {
-        let map = MapDescription::new(100, 50, 5);
-        match map.map_src_to_dest_range(51, 3) {
-            super::RangeType::Range(r) => {
-                assert_eq!(r.0, 101);
-                assert_eq!(r.1, 3);
-            }
-            _ => panic!(""),
-        }
-    }
-
-    #[test]
-    fn case4_e1() {
-        let map = MapDescription::new(100, 50, 5);
-        match map.map_src_to_dest_range(50, 5) {
-            super::RangeType::Range(r) => {
-                assert_eq!(r.0, 100);
-                assert_eq!(r.1, 5);
-            }
-            _ => panic!(""),
-        }
-    }
-
-    #[test]
-    fn case5() {
-        let map = MapDescription::new(100, 50, 5);
-        match map.map_src_to_dest_range(51, 6) {
-            super::RangeType::Range2R(r1, r2) => {
-                assert_eq!(r1.0, 101);
-                assert_eq!(r1.1, 4);
-
-                assert_eq!(r2.0, 55);
-                assert_eq!(r2.1, 2);
-            }
-            r => panic!("{:?}", r),
-        }
-    }
-
-    #[test]
-    fn case5_e1() {
-        let map = MapDescription::new(100, 50, 6);
-        match map.map_src_to_dest_range(55, 6) {
-            super::RangeType::Range2R(r1, r2) => {
-                assert_eq!(r1.0, 105);
-                assert_eq!(r1.1, 1);
-
-                assert_eq!(r2.0, 56);
-                assert_eq!(r2.1, 5);
-            }
-            r => panic!("{:?}", r),
-        }
-    }
-
-    #[test]
-    fn case5_e2() {
-        let map = MapDescription::new(100, 50, 5);
-        match map.map_src_to_dest_range(55, 6) {
-            super::RangeType::SameR => {}
-            r => panic!("{:?}", r),
-        }
-    }
+    use super::{Map, MapDescription};
 
-    #[test]
-    fn case6() {
-        let map = MapDescription::new(100, 50, 5);
-        match map.map_src_to_dest_range(49, 7) {
-            super::RangeType::Range3(r1, r2, r3) => {
-                assert_eq!(r1.0, 49);
-                assert_eq!(r1.1, 1);
-
-                assert_eq!(r2.0, 100);
-                assert_eq!(r2.1, 5);
-
-                assert_eq!(r3.0, 56);
-                assert_eq!(r3.1, 1);
-            }
-            r => panic!("{:?}", r),
-        }
+    fn map_through(desc: MapDescription, start: usize, len: usize) -> Vec<(usize, usize)> {
+        let mut map = Map::empty();
+        map.maps.push(desc);
+        map.get_des_by_range(&Interval::with_len(start, len).into())
+            .iter()
+            .map(|i| (i.start, i.len()))
+            .collect()
     }
 
     #[test]
-    fn case_extra1() {
-        let map = MapDescription::new(100, 50, 5);
-        match map.map_src_to_dest_range(50, 7) {
-            super::RangeType::Range2R(r1, r2) => {
-                assert_eq!(r1.0, 100);
-                assert_eq!(r1.1, 5);
-
-                assert_eq!(r2.0, 55);
-                assert_eq!(r2.1, 2);
-            }
-            r => panic!("{:?}", r),
-        }
+    fn test_get_des_by_range() {
+        // Before and after the source range
+        assert_eq!(
+            map_through(MapDescription::new(52, 50, 48), 10, 5),
+            [(10, 5)]
+        );
+        assert_eq!(
+            map_through(MapDescription::new(52, 50, 2), 54, 5),
+            [(54, 5)]
+        );
+        assert_eq!(
+            map_through(MapDescription::new(100, 50, 5), 45, 5),
+            [(45, 5)]
+        );
+        assert_eq!(
+            map_through(MapDescription::new(100, 50, 5), 55, 6),
+            [(55, 6)]
+        );
+
+        // Partly or fully inside it
+        assert_eq!(
+            map_through(MapDescription::new(100, 50, 5), 49, 3),
+            [(49, 1), (100, 2)]
+        );
+        assert_eq!(
+            map_through(MapDescription::new(100, 50, 5), 51, 3),
+            [(101, 3)]
+        );
+        assert_eq!(
+            map_through(MapDescription::new(100, 50, 5), 50, 5),
+            [(100, 5)]
+        );
+        assert_eq!(
+            map_through(MapDescription::new(100, 50, 5), 51, 6),
+            [(55, 2), (101, 4)]
+        );
+        assert_eq!(
+            map_through(MapDescription::new(100, 50, 6), 55, 6),
+            [(56, 5), (105, 1)]
+        );
+        assert_eq!(
+            map_through(MapDescription::new(100, 50, 5), 50, 7),
+            [(55, 2), (100, 5)]
+        );
+
+        // Covering it
+        assert_eq!(
+            map_through(MapDescription::new(100, 50, 5), 49, 7),
+            [(49, 1), (55, 1), (100, 5)]
+        );
+
+        let mut map = Map::empty();
+        map.maps.push(MapDescription::new(0, 10, 5));
+        map.maps.push(MapDescription::new(5, 0, 5));
+        let seeds = [Interval::new(3, 12), Interval::new(20, 21)];
+        assert_eq!(
+            map.get_des_by_range(&seeds.into_iter().collect()),
+            IntervalSet::from_iter([
+                Interval::new(0, 2),
+                Interval::new(5, 10),
+                Interval::new(20, 21)
+            ])
+        );
     }
-
-    // #[test]
-    // fn case_extra2() {
-    //     let map = MapDescription::new(49, 53, 8);
-    //     match map.map_src_to_dest_range(57, 1) {
-    //         r => panic!("{:?}", r),
-    //     }
-    // }
-
-    //49 53 8
 }
 
 impl std::fmt::Display for MapDescription {
@@ -412,6 +168,7 @@ impl std::fmt::Display for MapDescription {
     }
 }
 
+#[derive(Debug)]
 struct Map {
     name: String,
     maps: Vec<MapDescription>,
@@ -432,79 +189,72 @@ impl Map {
         }
     }
 
-    fn get_dest(&self, source: usize) -> usize {
-        let found = self.maps.iter().find(|m| {
-            if source < m.source_start || source > m.source_start + m.range_len {
-                return false;
+    /// `seed-to-soil map:` and a line of destination start, source start and
+    /// length for each range.
+    fn parse(block: &str) -> Result<Self, InputError> {
+        let (header, ranges) = block.split_once('\n').unwrap_or((block, ""));
+        let name = header
+            .trim()
+            .strip_suffix(" map:")
+            .ok_or(InputError::at_line(
+                1,
+                format!("expected `<name> map:`, found `{}`", header.trim()),
+            ))?;
+
+        let mut map = Map::new(name.to_string());
+        map.maps = lines(ranges, |l| {
+            let numbers = l
+                .split_whitespace()
+                .map(number)
+                .collect::<Result<Vec<_>, _>>()?;
+            match numbers[..] {
+                [dest, source, len] => Ok(MapDescription::new(dest, source, len)),
+                _ => Err(InputError::new(format!("expected 3 numbers in `{}`", l))),
             }
-            return true;
-        });
-
-        match found {
-            Some(m) => m.dest_start + (source - m.source_start),
-            None => source,
-        }
-    }
-
-    fn get_des_by_range(&self, range_start: usize, range_len: usize) -> Vec<(usize, usize)> {
-        let mut cur_range_start = range_start;
-        let mut cur_range_len = range_len;
-
-        let mut results: Vec<(usize, usize)> = Vec::new();
-        let mut higher = false;
-
-        for m in &self.maps {
-            match m.map_src_to_dest_range(cur_range_start, cur_range_len) {
-                RangeType::Same => {
-                    results.push((cur_range_start, cur_range_len));
-                    break;
-                }
-                RangeType::SameR => {
-                    higher = true;
-                    continue;
-                }
-                RangeType::Range(r) => {
-                    higher = false;
-                    results.push(r);
-                    break;
-                }
-                RangeType::Range2(r1, r2) => {
-                    higher = false;
-                    results.push(r1);
-                    results.push(r2);
-                    break;
-                }
-                RangeType::Range2R(r1, r2) => {
-                    higher = true;
-                    results.push(r1);
-
-                    cur_range_start = r2.0;
-                    cur_range_len = r2.1;
-                }
-                RangeType::Range3(r1, r2, r3) => {
-                    higher = true;
-                    results.push(r1);
-                    results.push(r2);
-
-                    cur_range_start = r3.0;
-                    cur_range_len = r3.1;
+        })
+        .and_then(|maps| {
+            for (i, m) in maps.iter().enumerate() {
+                if let Some(other) = maps[..i].iter().find(|o| o.source().overlaps(&m.source())) {
+                    return Err(InputError::at_line(
+                        i + 1,
+                        format!("source range overlaps `{}`", other),
+                    ));
                 }
             }
-        }
+            Ok(maps)
+        })
+        .map_err(|e| InputError {
+            line: e.line.map(|l| l + 1),
+            ..e
+        })?;
+        map.maps.sort();
+        Ok(map)
+    }
+
+    fn range_map(&self) -> RangeMap<usize> {
+        self.maps
+            .iter()
+            .map(|m| (m.source(), m.dest_start))
+            .collect()
+    }
 
-        if higher {
-            results.push((cur_range_start, cur_range_len))
-        }
-        results
+    fn get_dest(&self, source: usize) -> usize {
+        self.range_map().get(source)
+    }
+
+    /// Where every value of `ranges` ends up. Values no description covers stay
+    /// the same.
+    fn get_des_by_range(&self, ranges: &IntervalSet<usize>) -> IntervalSet<usize> {
+        self.range_map().map_set(ranges)
     }
 }
 
-impl<'a> std::fmt::Display for Map {
+impl std::fmt::Display for Map {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "| {} \n", self.name).expect("fmt");
+        writeln!(f, "| {} ", self.name).expect("fmt");
 
         for m in &self.maps {
-            write!(f, " - {} \n", m).expect("fmt");
+            writeln!(f, " - {} ", m).expect("fmt");
         }
 
         Ok(())
@@ -515,7 +265,10 @@ impl<'a> std::fmt::Display for Map {
 mod test2 {
     use std::fs;
 
-    use crate::day5::Input;
+    use crate::{
+        day5::Input,
+        interval::{Interval, IntervalSet},
+    };
 
     use super::{Map, MapDescription};
 
@@ -530,7 +283,7 @@ mod test2 {
         map.maps.push(MapDescription::new(1, 0, 69));
         map.maps.push(MapDescription::new(0, 69, 1));
 
-        let ranges = map.get_des_by_range(p.0, p.1);
+        let ranges = map.get_des_by_range(&Interval::with_len(p.0, p.1).into());
 
         println!("ranges={:?}", ranges);
     }
@@ -547,7 +300,7 @@ mod test2 {
         map.maps.push(MapDescription::new(56, 93, 4));
         // map.maps.push(MapDescription::new(0, 69, 1));
 
-        let ranges = map.get_des_by_range(p.0, p.1);
+        let ranges = map.get_des_by_range(&Interval::with_len(p.0, p.1).into());
 
         println!("ranges={:?}", ranges);
     }
@@ -555,45 +308,39 @@ mod test2 {
     #[test]
     fn test2() {
         let contents = fs::read_to_string("./problems/d5_test.txt").expect("");
-        let inp = Input::parse(contents);
-        let mut ranges: Vec<(usize, usize)> = inp.seeds_to_ranges();
-        // let mut ranges: Vec<(usize, usize)> = vec![(55, 13)];
+        let inp = Input::parse(&contents).unwrap();
+        let mut ranges = inp.seeds_to_ranges();
         for map in &inp.maps {
             println!("Look into map: {}", map);
 
-            let mut map_ranges: Vec<(usize, usize)> = Vec::new();
+            let mut map_ranges = IntervalSet::new();
 
-            for ran in ranges {
-                let expected = map.get_dest(ran.0);
+            for ran in ranges.iter() {
+                let expected = map.get_dest(ran.start);
 
-                println!("Expected: {} for start {}", expected, ran.0);
+                println!("Expected: {} for start {}", expected, ran.start);
 
-                let mut rs = map.get_des_by_range(ran.0, 1);
+                let rs = map.get_des_by_range(&Interval::with_len(ran.start, 1).into());
 
                 println!("rs={:?}", rs);
 
-                let found = rs.iter().find(|r| r.0 == expected);
-
-                assert!(found.is_some());
+                assert!(rs.contains(expected));
 
-                map_ranges.append(&mut rs);
+                map_ranges = map_ranges.union(&rs);
             }
 
             ranges = map_ranges;
             println!("New ranges: {:?}", ranges);
         }
 
-        let mut m = usize::MAX;
-
-        for r in &ranges {
-            m = m.min(r.0);
-        }
+        let m = ranges.min().unwrap();
 
         println!("=Ranges: {:?}", ranges);
         println!("=Min: {}", m);
     }
 }
 
+#[derive(Debug)]
 pub struct Input {
     seeds: Vec<usize>,
     maps: Vec<Map>,
@@ -606,24 +353,16 @@ impl std::fmt::Display for Input {
             .iter()
             .map(|n| format!("{}, ", n))
             .collect::<String>();
-        write!(f, "Seeds: {} \n", seeds).expect("fmt");
+        writeln!(f, "Seeds: {} ", seeds).expect("fmt");
 
         for m in &self.maps {
-            write!(f, "- Map: {} \n", m).expect("fmt");
+            writeln!(f, "- Map: {} ", m).expect("fmt");
         }
 
         Ok(())
     }
 }
 
-fn get_num(v: &Vec<&str>, index: usize) -> usize {
-    return v
-        .get(index)
-        .expect("Index must exist")
-        .parse()
-        .expect("Number can be parsed");
-}
-
 impl Input {
     fn new() -> Self {
         Self {
@@ -632,78 +371,48 @@ impl Input {
         }
     }
 
-    fn seeds_to_ranges(&self) -> Vec<(usize, usize)> {
-        let mut i = self.seeds.iter();
-
-        let mut ranges: Vec<(usize, usize)> = Vec::new();
+    /// All the maps, from seed to location, as a single one.
+    fn seed_to_location(&self) -> RangeMap<usize> {
+        self.maps
+            .iter()
+            .fold(RangeMap::new(), |acc, map| acc.then(&map.range_map()))
+    }
 
-        loop {
-            let s1 = match i.next() {
-                Some(s) => s,
-                None => break,
-            };
+    /// The seeds line read as pairs of range start and length.
+    fn seeds_to_ranges(&self) -> IntervalSet<usize> {
+        let mut i = self.seeds.iter();
 
-            let s2 = match i.next() {
-                Some(s) => s,
-                None => break,
-            };
+        let mut ranges = IntervalSet::new();
 
-            ranges.push((*s1, *s2));
+        while let (Some(s1), Some(s2)) = (i.next(), i.next()) {
+            ranges.insert(Interval::with_len(*s1, *s2));
         }
 
         ranges
     }
 
-    fn parse(str: String) -> Input {
+    /// The `seeds:` line, then a block of ranges for each map.
+    fn parse(contents: &str) -> Result<Input, InputError> {
         let mut inp = Input::new();
-        let mut in_map: bool = false;
-
-        let mut cur_maps: Map = Map::new(String::from(""));
-
-        for line in str.split("\n") {
-            if line.trim().is_empty() {
-                if in_map {
-                    cur_maps.maps.sort();
-                    inp.maps.push(cur_maps);
-                    cur_maps = Map::empty();
+        let mut seeds = None;
+        blocks(contents, |block| {
+            match block.strip_prefix("seeds:") {
+                Some(numbers) => {
+                    let numbers = numbers
+                        .split_whitespace()
+                        .map(number)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if numbers.is_empty() {
+                        return Err(InputError::new("no seeds"));
+                    }
+                    seeds = Some(numbers)
                 }
-                in_map = false;
-                continue;
+                None => inp.maps.push(Map::parse(block)?),
             }
-
-            if line.contains("seeds:") {
-                let seeds: Vec<usize> = line
-                    .split_once(": ")
-                    .unwrap()
-                    .1
-                    .split_whitespace()
-                    .map(|n| n.parse().unwrap())
-                    .collect();
-                inp.seeds = seeds;
-            }
-
-            if line.contains(" map:") {
-                cur_maps = Map::new(line.replace(" map:", "").to_string());
-                in_map = true;
-                continue;
-            }
-
-            if in_map {
-                let s = line.split_whitespace().collect::<Vec<_>>();
-                cur_maps.maps.push(MapDescription::new(
-                    get_num(&s, 0),
-                    get_num(&s, 1),
-                    get_num(&s, 2),
-                ))
-            }
-        }
-
-        if in_map {
-            cur_maps.maps.sort();
-            inp.maps.push(cur_maps);
-        }
-
-        inp
+            Ok(())
+        })?;
+        inp.seeds = seeds.ok_or(InputError::new("missing `seeds:` line"))?;
+        Ok(inp)
     }
 }
 
@@ -712,20 +421,24 @@ mod tests {
 
     use std::fs;
 
-    use super::Input;
+    use crate::interval::{Interval, IntervalSet};
+
+    use crate::solution::Solution;
+
+    use super::{Day5, Input};
 
     #[test]
     fn p1_single_seed() {
         let contents = fs::read_to_string("./problems/d5.txt").expect("");
-        let inp = Input::parse(contents);
+        let inp = Input::parse(&contents).unwrap();
 
         println!("{}", inp);
 
-        let mut d: usize = *inp.seeds.get(0).unwrap();
+        let mut d: usize = *inp.seeds.first().unwrap();
         for map in &inp.maps {
             print!("= Source ({}) {}", &map.name, d);
             d = map.get_dest(d);
-            print!(" maps to dest {}\n", d);
+            println!(" maps to dest {}", d);
         }
 
         // for seed in inp.seeds {
@@ -741,7 +454,7 @@ mod tests {
     #[test]
     fn p1_all_seeds() {
         let contents = fs::read_to_string("./problems/d5.txt").expect("");
-        let inp = Input::parse(contents);
+        let inp = Input::parse(&contents).unwrap();
 
         println!("{}", inp);
 
@@ -753,7 +466,7 @@ mod tests {
             for map in &inp.maps {
                 print!("= Source ({}) {}", &map.name, d);
                 d = map.get_dest(d);
-                print!(" maps to dest {}\n", d);
+                println!(" maps to dest {}", d);
             }
             lowest = lowest.min(d);
         }
@@ -764,7 +477,7 @@ mod tests {
     #[test]
     fn p2_single_range() {
         let contents = fs::read_to_string("./problems/d5.txt").expect("");
-        let inp = Input::parse(contents);
+        let inp = Input::parse(&contents).unwrap();
 
         println!("{}", inp);
 
@@ -785,25 +498,25 @@ mod tests {
 
             for map in &inp.maps {
                 println!("Look into map: {}", map);
-                let rs = map.get_des_by_range(r, rsize);
+                let rs = map.get_des_by_range(&Interval::with_len(r, rsize).into());
 
                 let expected = map.get_dest(r);
 
                 println!("Expected: {}", expected);
                 println!("Found matching ranges: {:?}", rs);
 
-                let m = rs.iter().find(|first_range| first_range.0 == expected);
+                let m = rs.iter().find(|first_range| first_range.start == expected);
 
                 if m.is_none() {
-                    let diff = rs.iter().map(|r| (r.0, expected)).collect::<Vec<_>>();
+                    let diff = rs.iter().map(|r| (r.start, expected)).collect::<Vec<_>>();
                     println!("DIFF = {:?}, map={}", diff, map.name);
                 }
 
                 assert!(m.is_some());
 
                 if let Some(mr) = m {
-                    r = mr.0;
-                    rsize = mr.1;
+                    r = mr.start;
+                    rsize = mr.len();
                 }
 
                 r_ranges.push(rs);
@@ -815,33 +528,21 @@ mod tests {
     #[test]
     fn p2_full_test_one_range() {
         let contents = fs::read_to_string("./problems/d5.txt").expect("");
-        let inp = Input::parse(contents);
+        let inp = Input::parse(&contents).unwrap();
 
         // println!("{}", inp);
 
         // let mut lowest = usize::MAX;
 
-        // let mut ranges: Vec<(usize, usize)> = inp.seeds_to_ranges();
-        let mut ranges: Vec<(usize, usize)> = vec![(55, 13)];
+        let mut ranges: IntervalSet<usize> = Interval::with_len(55, 13).into();
         for map in &inp.maps {
             println!("Look into map: {}", map);
 
-            let mut map_ranges: Vec<(usize, usize)> = Vec::new();
-
-            for ran in ranges {
-                let mut rs = map.get_des_by_range(ran.0, ran.1);
-                map_ranges.append(&mut rs);
-            }
-
-            ranges = map_ranges;
+            ranges = map.get_des_by_range(&ranges);
             println!("New ranges: {:?}", ranges);
         }
 
-        let mut m = usize::MAX;
-
-        for r in &ranges {
-            m = m.min(r.0);
-        }
+        let m = ranges.min().unwrap();
 
         println!("=Ranges: {:?}", ranges);
         println!("=Min: {}", m);
@@ -850,32 +551,23 @@ mod tests {
     #[test]
     fn p2_full_test_single_ranges() {
         let contents = fs::read_to_string("./problems/d5.txt").expect("");
-        let inp = Input::parse(contents);
+        let inp = Input::parse(&contents).unwrap();
 
         println!("{}", inp);
 
         // let mut lowest = usize::MAX;
 
-        // let mut ranges: Vec<(usize, usize)> = inp.seeds_to_ranges();
-        let mut ranges: Vec<(usize, usize)> = vec![(55, 1), (79, 1), (14, 1), (13, 1)];
+        let mut ranges: IntervalSet<usize> = [55, 79, 14, 13]
+            .into_iter()
+            .map(|start| Interval::with_len(start, 1))
+            .collect();
         for map in &inp.maps {
             println!("Look into map: {}", map);
 
-            let mut map_ranges: Vec<(usize, usize)> = Vec::new();
-
-            for ran in ranges {
-                let mut rs = map.get_des_by_range(ran.0, ran.1);
-                map_ranges.append(&mut rs);
-            }
-
-            ranges = map_ranges;
+            ranges = map.get_des_by_range(&ranges);
         }
 
-        let mut m = usize::MAX;
-
-        for r in &ranges {
-            m = m.min(r.0);
-        }
+        let m = ranges.min().unwrap();
 
         println!("=Ranges: {:?}", ranges);
         println!("=Min: {}", m);
@@ -884,36 +576,25 @@ mod tests {
     #[test]
     fn p2() {
         let contents = fs::read_to_string("./problems/d5.txt").expect("");
-        let inp = Input::parse(contents);
+        let inp = Input::parse(&contents).unwrap();
 
         println!("{}", inp);
 
         // let mut lowest = usize::MAX;
 
-        let mut ranges: Vec<(usize, usize)> = inp.seeds_to_ranges();
+        let mut ranges = inp.seeds_to_ranges();
 
         println!("Inital ranges: {:?}", ranges);
 
         for map in &inp.maps {
             println!("\nLook into map: {}", map);
 
-            let mut map_ranges: Vec<(usize, usize)> = Vec::new();
-
-            for ran in ranges {
-                let mut rs = map.get_des_by_range(ran.0, ran.1);
-                map_ranges.append(&mut rs);
-            }
-
-            ranges = map_ranges;
+            ranges = map.get_des_by_range(&ranges);
 
             println!("New ranges: {:?}", ranges);
         }
 
-        let mut m = usize::MAX;
-
-        for r in &ranges {
-            m = m.min(r.0);
-        }
+        let m = ranges.min().unwrap();
 
         println!("=Ranges: {:?}", ranges);
         println!("=Min: {}", m);
@@ -929,4 +610,35 @@ mod tests {
         assert!(range.contains(&15));
         assert!(!range.contains(&20));
     }
+
+    #[test]
+    fn test_bad_input() {
+        let err = |contents| Day5::parse(contents).unwrap_err().to_string();
+        assert_eq!(
+            err("seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50\n"),
+            "line 5: expected 3 numbers in `52 50`"
+        );
+        assert_eq!(
+            err("seeds: 79 x4\n\nseed-to-soil map:\n50 98 2\n"),
+            "line 1: not a number: `x4`"
+        );
+        assert_eq!(
+            err("seeds: 79 14\n\nseed-to-soil:\n50 98 2\n"),
+            "line 3: expected `<name> map:`, found `seed-to-soil:`"
+        );
+        assert_eq!(err("seed-to-soil map:\n50 98 2\n"), "missing `seeds:` line");
+        assert_eq!(
+            err("seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 49\n"),
+            "line 5: source range overlaps `50 98 2`"
+        );
+        assert_eq!(
+            err("seeds:\n\nseed-to-soil map:\n50 98 2\n"),
+            "line 1: no seeds"
+        );
+
+        // A single number is a seed for part 1, but not a range for part 2
+        let input = Day5::parse("seeds: 79\n\nseed-to-soil map:\n50 98 2\n").unwrap();
+        assert_eq!(Day5::part1(&input), 79.into());
+        assert_eq!(Day5::part2(&input), "no seed ranges".into());
+    }
 }
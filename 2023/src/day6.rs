@@ -1,47 +1,68 @@
 #![allow(dead_code)]
 
-use std::fs;
-
-use once_cell::sync::Lazy;
-use regex::Regex;
-
-fn parse_input() -> Vec<(i64, i64)> {
-    static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+)").unwrap());
-
-    let contents = fs::read_to_string("./problems/d6.txt").expect("");
-    let data: Vec<Vec<_>> = contents
-        .split("\n")
-        .map(|l| {
-            l.trim()
-                .split_whitespace()
-                .skip(1)
-                .map(|col| col.trim())
-                .map(|col| col.parse().expect("Could not parse number"))
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
-
-    std::iter::zip(&data[0], &data[1])
-        .map(|pair| (*pair.0, *pair.1))
-        .collect::<Vec<_>>()
+use crate::{
+    input::InputError,
+    parsing::{ints, key_values},
+    solution::{Answer, Solution},
+};
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    /// Time and record distance of every race
+    type Input = Vec<(i64, i64)>;
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        parse_input(contents)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        input
+            .iter()
+            .map(|(time, record_dist)| find_hold_time(*time, *record_dist))
+            .product::<i64>()
+            .into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        let (time, record_dist) = join_races(input);
+        find_hold_time(time, record_dist).into()
+    }
 }
 
-fn parse_input2() -> (i64, i64) {
-    let contents = fs::read_to_string("./problems/d6.txt").expect("");
-    let data: Vec<_> = contents
-        .split("\n")
-        .map(|l| {
-            l.trim()
-                .split_whitespace()
-                .skip(1)
-                .map(|col| col.trim())
-                .flat_map(|col| col.chars())
-                .collect::<String>()
-        })
-        .map(|r| r.parse().expect("Should parse number"))
-        .collect::<Vec<_>>();
-
-    (data[0], data[1])
+fn parse_input(contents: &str) -> Result<Vec<(i64, i64)>, InputError> {
+    let rows = key_values(contents)?;
+    let row = |key: &str| match rows.iter().find(|(k, _)| *k == key) {
+        Some((_, values)) => ints::<i64>(values),
+        None => Err(InputError::new(format!("missing `{}:` line", key))),
+    };
+
+    let (times, dists) = (row("Time")?, row("Distance")?);
+    if times.len() != dists.len() {
+        return Err(InputError::new(format!(
+            "{} times but {} distances",
+            times.len(),
+            dists.len()
+        )));
+    }
+    Ok(std::iter::zip(times, dists).collect())
+}
+
+/// With bad kerning the numbers of all races are a single race.
+fn join_races(races: &[(i64, i64)]) -> (i64, i64) {
+    let (time, dist): (String, String) = races
+        .iter()
+        .map(|(time, dist)| (time.to_string(), dist.to_string()))
+        .unzip();
+
+    (
+        time.parse().expect("Should parse number"),
+        dist.parse().expect("Should parse number"),
+    )
+}
+
+fn parse_input2(contents: &str) -> Result<(i64, i64), InputError> {
+    Ok(join_races(&parse_input(contents)?))
 }
 
 fn find_hold_time(race_time: i64, record_dist: i64) -> i64 {
@@ -59,8 +80,7 @@ fn find_hold_time(race_time: i64, record_dist: i64) -> i64 {
         }
     }
 
-    println!("max_charge={}", max_charge);
-    return max_charge;
+    max_charge
 }
 
 #[cfg(test)]
@@ -75,7 +95,8 @@ mod tests {
 
     #[test]
     fn p1() {
-        let races = parse_input();
+        let contents = fs::read_to_string("./problems/d6.txt").expect("");
+        let races = parse_input(&contents).unwrap();
 
         let mut result = 1;
         for (time, record_dist) in &races {
@@ -87,7 +108,8 @@ mod tests {
 
     #[test]
     fn p2() {
-        let (time, record_dist) = parse_input2();
+        let contents = fs::read_to_string("./problems/d6.txt").expect("");
+        let (time, record_dist) = parse_input2(&contents).unwrap();
         let result = find_hold_time(time, record_dist);
 
         println!("{:?}", result);
@@ -116,7 +138,7 @@ mod tests {
         println!("{:?}", data);
     }
 
-    fn parse_sync(contents: &String, re: &Regex) -> Vec<Vec<i64>> {
+    fn parse_sync(contents: &str, re: &Regex) -> Vec<Vec<i64>> {
         contents
             .split("\n")
             .map(|str| {
@@ -128,7 +150,7 @@ mod tests {
             .collect::<Vec<_>>()
     }
 
-    async fn parse_async(contents: &String, re: &Regex) -> Vec<Vec<i64>> {
+    async fn parse_async(contents: &str, re: &Regex) -> Vec<Vec<i64>> {
         let stream = tokio_stream::iter(contents.split("\n"))
             .map(|str| {
                 re.captures_iter(str)
@@ -2,8 +2,75 @@
 
 use std::collections::HashMap;
 
+use crate::{
+    input::InputError,
+    parsing::{lines, number, pair},
+    solution::{Answer, Solution},
+};
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    /// The cards of each hand and its bet
+    type Input = Vec<(String, i64)>;
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        lines(contents, parse_hand)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        total_winnings(
+            input
+                .iter()
+                .map(|(hand, bet)| Hand::new(hand.clone(), *bet))
+                .collect(),
+        )
+        .into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        total_winnings(
+            input
+                .iter()
+                .map(|(hand, bet)| Hand::new2(hand.clone(), *bet))
+                .collect(),
+        )
+        .into()
+    }
+}
+
+fn total_winnings(mut hands: Vec<Hand>) -> i64 {
+    hands.sort();
+    hands
+        .iter()
+        .enumerate()
+        .map(|(i, hand)| hand.bet * (i as i64 + 1))
+        .sum()
+}
+
 const JOKER: i32 = 1;
 
+const CARDS: &str = "AKQJT98765432";
+
+/// `32T3K 765`
+fn parse_hand(line: &str) -> Result<(String, i64), InputError> {
+    let (hand, bet) = pair(line, " ")?;
+    if let Some((i, c)) = hand.char_indices().find(|(_, c)| !CARDS.contains(*c)) {
+        return Err(InputError {
+            col: Some(i + 1),
+            ..InputError::new(format!("unexpected card `{}`", c))
+        });
+    }
+    if hand.len() != 5 {
+        return Err(InputError::new(format!(
+            "expected 5 cards, found {} in `{}`",
+            hand.len(),
+            hand
+        )));
+    }
+    Ok((hand.to_string(), number(bet)?))
+}
+
 fn card_to_value(c: char) -> i32 {
     match c {
         'A' => 14,
@@ -53,7 +120,7 @@ enum HandType {
     Five,
 }
 
-#[derive(Debug, Eq, Ord)]
+#[derive(Debug, Eq)]
 struct Hand {
     cards: Vec<i32>,
     counts: HandType,
@@ -67,18 +134,22 @@ impl PartialEq for Hand {
     }
 }
 
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.counts
+            .cmp(&other.counts)
+            .then_with(|| self.cards.cmp(&other.cards))
+    }
+}
+
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match self.counts.partial_cmp(&other.counts) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
-        }
-        self.cards.partial_cmp(&other.cards)
+        Some(self.cmp(other))
     }
 }
 
 impl Hand {
-    fn new(hand: String, bet: String) -> Self {
+    fn new(hand: String, bet: i64) -> Self {
         let cards = hand.chars().map(card_to_value).collect::<Vec<_>>();
         // mh.sort();
         let counts: HashMap<i32, i32> = cards.iter().fold(HashMap::new(), |mut acc, el| {
@@ -86,7 +157,7 @@ impl Hand {
             acc
         });
 
-        let mut count_values = counts.iter().map(|(_, value)| value).collect::<Vec<_>>();
+        let mut count_values = counts.values().collect::<Vec<_>>();
 
         count_values.sort_by(|a, b| b.cmp(a));
 
@@ -110,12 +181,12 @@ impl Hand {
         Self {
             cards,
             counts: hand_type,
-            bet: bet.parse().unwrap(),
+            bet,
             str: hand,
         }
     }
 
-    fn new2(hand: String, bet: String) -> Self {
+    fn new2(hand: String, bet: i64) -> Self {
         let cards = hand.chars().map(card_to_value2).collect::<Vec<_>>();
         // mh.sort();
         let mut counts: HashMap<i32, i32> = cards.iter().fold(HashMap::new(), |mut acc, el| {
@@ -145,7 +216,6 @@ impl Hand {
         counts = counts
             .iter()
             .fold(HashMap::new(), |mut acc, (card, count)| {
-                println!("{}", hand);
                 if jokers == 5 {
                     acc.insert(*card, *count);
                     return acc;
@@ -162,7 +232,7 @@ impl Hand {
                 acc
             });
 
-        let mut count_values = counts.iter().map(|(_, value)| value).collect::<Vec<_>>();
+        let mut count_values = counts.values().collect::<Vec<_>>();
 
         count_values.sort_by(|a, b| b.cmp(a));
 
@@ -186,7 +256,7 @@ impl Hand {
         Self {
             cards,
             counts: hand_type,
-            bet: bet.parse().unwrap(),
+            bet,
             str: hand,
         }
     }
@@ -196,7 +266,9 @@ impl Hand {
 mod tests {
     use std::fs;
 
-    use crate::day7::Hand;
+    use crate::{day7::Hand, solution::Solution};
+
+    use super::Day7;
 
     #[test]
     fn p1() {
@@ -204,8 +276,7 @@ mod tests {
 
         let s = contents
             .split("\n")
-            .map(|l| l.split_once(" "))
-            .filter_map(|l| l)
+            .filter_map(|l| l.split_once(" "))
             .collect::<Vec<_>>();
 
         // println!("{:?}", s);
@@ -214,7 +285,7 @@ mod tests {
 
         let mut hands = s
             .iter()
-            .map(|(hand, bet)| Hand::new(hand.to_string(), bet.to_string()))
+            .map(|(hand, bet)| Hand::new(hand.to_string(), bet.parse().unwrap()))
             .collect::<Vec<_>>();
 
         hands.sort();
@@ -222,11 +293,9 @@ mod tests {
         // println!("- {:?}", hands);
 
         let mut profit: i64 = 0;
-        let mut i: i64 = 1;
-        for hand in hands {
+        for (i, hand) in (1..).zip(hands) {
             println!("hand = {:?}", hand);
             profit += hand.bet * i;
-            i += 1;
         }
 
         println!("profit={}", profit);
@@ -238,8 +307,7 @@ mod tests {
 
         let s = contents
             .split("\n")
-            .map(|l| l.split_once(" "))
-            .filter_map(|l| l)
+            .filter_map(|l| l.split_once(" "))
             .collect::<Vec<_>>();
 
         // println!("{:?}", s);
@@ -248,7 +316,7 @@ mod tests {
 
         let mut hands = s
             .iter()
-            .map(|(hand, bet)| Hand::new2(hand.to_string(), bet.to_string()))
+            .map(|(hand, bet)| Hand::new2(hand.to_string(), bet.parse().unwrap()))
             .collect::<Vec<_>>();
 
         hands.sort();
@@ -256,13 +324,26 @@ mod tests {
         // println!("- {:?}", hands);
 
         let mut profit: i64 = 0;
-        let mut i: i64 = 1;
-        for hand in hands {
+        for (i, hand) in (1..).zip(hands) {
             println!("hand = {:?}", hand);
             profit += hand.bet * i;
-            i += 1;
         }
 
         println!("profit={}", profit);
     }
+
+    #[test]
+    fn test_bad_input() {
+        let err = |contents| Day7::parse(contents).unwrap_err().to_string();
+        assert_eq!(
+            err("32T3K 765\nT5X5J 684"),
+            "line 2, column 3: unexpected card `X`"
+        );
+        assert_eq!(
+            err("32T3K 765\nT55J 684"),
+            "line 2: expected 5 cards, found 4 in `T55J`"
+        );
+        assert_eq!(err("32T3K 7x5"), "line 1: not a number: `7x5`");
+        assert_eq!(err("32T3K"), "line 1: expected ` ` in `32T3K`");
+    }
 }
@@ -1,14 +1,80 @@
 #![allow(dead_code)]
 
-use num_integer::lcm;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
 use tokio_stream::StreamExt; // Trait for primitive integer types
 
-fn lcm_list(list: Vec<i64>) -> i64 // Restrict T to types that are primitive integers
-{
-    list.iter().cloned().fold(1, |acc, x| lcm(acc, x))
+use crate::{
+    cycle,
+    input::InputError,
+    math,
+    parsing::pair,
+    solution::{Answer, Solution},
+};
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = (String, Map);
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        let mut it = contents.lines().enumerate();
+
+        let first = it.next().map(|(_, l)| l.trim()).unwrap_or_default();
+        if first.is_empty() {
+            return Err(InputError::at_line(1, "no instructions"));
+        }
+        if let Some(c) = first.chars().find(|&c| c != 'L' && c != 'R') {
+            return Err(InputError::at_line(1, format!("unexpected `{}`", c)));
+        }
+
+        let nodes = it
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| Ok((i + 1, parse_node(l).map_err(|e| e.on_line(i + 1))?)))
+            .collect::<Result<Vec<_>, InputError>>()?;
+
+        let map: Map = nodes.iter().map(|(_, node)| node.clone()).collect();
+        for (line, (_, (left, right))) in &nodes {
+            if let Some(to) = [left, right].into_iter().find(|to| !map.contains_key(*to)) {
+                return Err(InputError::at_line(*line, format!("undefined node {}", to)));
+            }
+        }
+
+        Ok((first.to_string(), map))
+    }
+
+    fn part1((first, map): &Self::Input) -> Answer {
+        if !map.contains_key("AAA") {
+            return "no AAA node".into();
+        }
+        let mut dir = Direction::new(first.clone());
+        traverse(map, &"AAA".to_string(), &mut dir).into()
+    }
+
+    fn part2((first, map): &Self::Input) -> Answer {
+        static START_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"A$").unwrap());
+
+        let instructions = first.chars().collect::<Vec<_>>();
+        let ghosts = map
+            .keys()
+            .filter(|start| START_RE.is_match(start))
+            .map(|start| arrivals(map, &instructions, start))
+            .collect::<Vec<_>>();
+
+        match all_arrive(&ghosts) {
+            Ok(Some(steps)) => steps.into(),
+            Ok(None) => "the ghosts never all arrive together".into(),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+/// `AAA = (BBB, CCC)`
+fn parse_node(line: &str) -> Result<(String, (String, String)), InputError> {
+    let (from, to) = pair(line, "=")?;
+    let (left, right) = pair(to.trim_matches(['(', ')']), ",")?;
+    Ok((from.to_string(), (left.to_string(), right.to_string())))
 }
 
 type Map = HashMap<String, (String, String)>;
@@ -28,7 +94,7 @@ fn traverse(map: &Map, start: &String, dir: &mut Direction) -> i64 {
         count += 1;
     }
 
-    return count;
+    count
 }
 
 async fn traverse2(map: &Map, starts: Vec<&String>, dir: &mut Direction) -> i64 {
@@ -65,7 +131,7 @@ async fn traverse2(map: &Map, starts: Vec<&String>, dir: &mut Direction) -> i64
         }
     }
 
-    return count;
+    count
 }
 
 fn traverse3(map: &Map, start: &String, dir: &mut Direction) -> i64 {
@@ -73,7 +139,7 @@ fn traverse3(map: &Map, start: &String, dir: &mut Direction) -> i64 {
     let mut count = 0;
     static END_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Z$").unwrap());
 
-    while !END_RE.is_match(&cur) {
+    while !END_RE.is_match(cur) {
         // println!("cur={}", cur);
         let (left, right) = map.get(cur).expect("Get key");
         cur = match dir.next() {
@@ -84,7 +150,96 @@ fn traverse3(map: &Map, start: &String, dir: &mut Direction) -> i64 {
         count += 1;
     }
 
-    return count;
+    count
+}
+
+/// When a ghost stands on a node ending in `Z`: the steps in `once` before its
+/// walk starts repeating, then each of `repeating` plus any multiple of `period`.
+struct Arrivals {
+    once: Vec<i64>,
+    repeating: Vec<i64>,
+    period: i64,
+}
+
+impl Arrivals {
+    fn at(&self, step: i64) -> bool {
+        self.once.contains(&step)
+            || self
+                .repeating
+                .iter()
+                .any(|&r| step >= r && (step - r) % self.period == 0)
+    }
+}
+
+/// Walks from `start` until the ghost is back on a node at the same point of
+/// the instructions, after which it goes around the same loop forever.
+fn arrivals(map: &Map, instructions: &[char], start: &str) -> Arrivals {
+    let step = |&(node, i): &(&str, usize)| {
+        let (left, right) = &map[node];
+        let next = if instructions[i] == 'L' { left } else { right };
+        (next.as_str(), (i + 1) % instructions.len())
+    };
+    let cycle = cycle::hashed(&(start, 0), step);
+
+    let mut arrivals = Arrivals {
+        once: Vec::new(),
+        repeating: Vec::new(),
+        period: cycle.len as i64,
+    };
+    let mut state = (start, 0);
+    for t in 0..cycle.start + cycle.len {
+        if state.0.ends_with('Z') {
+            if t < cycle.start {
+                arrivals.once.push(t as i64);
+            } else {
+                arrivals.repeating.push(t as i64);
+            }
+        }
+        state = step(&state);
+    }
+    arrivals
+}
+
+/// The first step where every ghost stands on a node ending in `Z`, none if
+/// they never do. Ghosts have to loop with a positive period.
+fn all_arrive(ghosts: &[Arrivals]) -> Result<Option<i64>, String> {
+    if let Some(g) = ghosts.iter().find(|g| g.period <= 0) {
+        return Err(format!("ghost loops with a period of {}", g.period));
+    }
+
+    let early = ghosts
+        .iter()
+        .flat_map(|g| g.once.iter().copied())
+        .filter(|&t| ghosts.iter().all(|g| g.at(t)))
+        .min();
+
+    // Otherwise it's in the loops. Adding the ghosts one at a time, the steps
+    // where all of them so far arrive together are some residues modulo the
+    // lcm of their periods, each from the last of the arrivals lined up.
+    let mut modulus = 1;
+    let mut together = HashMap::from([(0, 0)]);
+    for g in ghosts {
+        let mut next: HashMap<i64, i64> = HashMap::new();
+        for (&residue, &from) in &together {
+            for &r in &g.repeating {
+                if let Some((residue, _)) =
+                    math::crt([(residue, modulus), (r % g.period, g.period)])
+                {
+                    let from = from.max(r);
+                    let earliest = next.entry(residue).or_insert(from);
+                    *earliest = (*earliest).min(from);
+                }
+            }
+        }
+        modulus = math::lcm_all([modulus, g.period]);
+        together = next;
+    }
+    let late = together
+        .iter()
+        .map(|(&residue, &from)| math::first_from(residue, modulus, from))
+        .min();
+
+    Ok(early.or(late))
 }
 
 struct Direction {
@@ -115,15 +270,19 @@ mod tests {
     use once_cell::sync::Lazy;
     use regex::Regex;
 
-    use crate::day8::{traverse, traverse2, traverse3, Direction};
+    use crate::{
+        day8::{traverse, traverse2, traverse3, Direction},
+        math,
+        solution::Solution,
+    };
 
-    use super::lcm_list;
+    use super::{all_arrive, Arrivals, Day8};
 
     #[test]
     fn p1() {
         let contents = fs::read_to_string("./problems/d8.txt").expect("");
 
-        let mut it = contents.split("\n").into_iter();
+        let mut it = contents.split("\n");
 
         let first = it.next().unwrap();
 
@@ -155,7 +314,7 @@ mod tests {
     async fn p2() {
         let contents = fs::read_to_string("./problems/d8.txt").expect("");
 
-        let mut it = contents.split("\n").into_iter();
+        let mut it = contents.split("\n");
 
         static START_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"A$").unwrap());
         static END_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Z$").unwrap());
@@ -179,14 +338,8 @@ mod tests {
         let mut dir = Direction::new(first.to_string());
 
         let starts: Vec<&String> = x
-            .iter()
-            .filter_map(|(start, _)| {
-                if START_RE.is_match(start) {
-                    Some(start)
-                } else {
-                    None
-                }
-            })
+            .keys()
+            .filter(|start| START_RE.is_match(start))
             .collect::<Vec<_>>();
 
         println!("starts={}", starts.len());
@@ -199,7 +352,7 @@ mod tests {
     #[test]
     fn p2_alt() {
         let contents = fs::read_to_string("./problems/d8.txt").expect("");
-        let mut it = contents.split("\n").into_iter();
+        let mut it = contents.split("\n");
 
         let first = it.next().unwrap();
 
@@ -224,25 +377,72 @@ mod tests {
         static START_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"A$").unwrap());
 
         let starts: Vec<&String> = x
-            .iter()
-            .filter_map(|(start, _)| {
-                if START_RE.is_match(start) {
-                    Some(start)
-                } else {
-                    None
-                }
-            })
+            .keys()
+            .filter(|start| START_RE.is_match(start))
             .collect::<Vec<_>>();
 
         println!("starts={}", starts.len());
 
         let mut results = Vec::new();
         for s in starts {
-            let result = traverse3(&x, &s, &mut dir);
+            let result = traverse3(&x, s, &mut dir);
             results.push(result);
         }
 
-        println!("result: {}", lcm_list(results))
+        println!("result: {}", math::lcm_all(results))
+    }
+
+    #[test]
+    fn all_arrive_test() {
+        let ghost = |once: &[i64], repeating: &[i64], period| Arrivals {
+            once: once.to_vec(),
+            repeating: repeating.to_vec(),
+            period,
+        };
+
+        // Loops that start at zero line up at the lcm
+        assert_eq!(
+            all_arrive(&[ghost(&[], &[2], 2), ghost(&[], &[3], 3)]),
+            Ok(Some(6))
+        );
+        // Offset loops don't
+        assert_eq!(
+            all_arrive(&[ghost(&[], &[5], 4), ghost(&[], &[3], 6)]),
+            Ok(Some(9))
+        );
+        assert_eq!(
+            all_arrive(&[ghost(&[], &[5], 4), ghost(&[], &[4], 6)]),
+            Ok(None)
+        );
+        // Before the second ghost starts looping
+        assert_eq!(
+            all_arrive(&[ghost(&[], &[1], 1), ghost(&[2], &[7], 5)]),
+            Ok(Some(2))
+        );
+        // Each ghost arrives twice a loop, lined up one ghost at a time
+        let many = (0..12)
+            .map(|i| ghost(&[], &[1, 2 + i], 3 + i))
+            .collect::<Vec<_>>();
+        let first = (1..).find(|&t| many.iter().all(|g| g.at(t)));
+        assert!(first.is_some());
+        assert_eq!(all_arrive(&many), Ok(first));
+
+        assert_eq!(
+            all_arrive(&[ghost(&[], &[1], 2), ghost(&[], &[0], 0)]),
+            Err("ghost loops with a period of 0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_bad_input() {
+        let err = |contents| Day8::parse(contents).unwrap_err().to_string();
+
+        assert_eq!(err("\n\nAAA = (AAA, AAA)"), "line 1: no instructions");
+        assert_eq!(err("LRX\n\nAAA = (AAA, AAA)"), "line 1: unexpected `X`");
+        assert_eq!(
+            err("LR\n\nAAA = (BBB, AAA)\nCCC = (AAA, DDD)"),
+            "line 3: undefined node BBB"
+        );
     }
 
     // #[test]
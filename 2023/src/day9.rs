@@ -1,71 +1,105 @@
 #![allow(dead_code)]
 
-#[cfg(test)]
-mod tests {
-    use std::fs;
+use crate::{
+    input::InputError,
+    solution::{Answer, Solution},
+};
 
-    fn find_pattern(line: Vec<i64>) -> (Vec<i64>, i64, i64, i64) {
-        let mut res = Vec::new();
-        let mut prev = 0;
-        let mut zeros = 0;
-        let mut last = 0;
-        let mut first = 0;
-        for (i, n) in line.iter().enumerate() {
-            if i == 0 {
-                prev = *n;
-                first = *n;
-            } else {
-                res.push(n - prev);
-                zeros += if (n - prev) == 0 { 1 } else { 0 };
-                prev = *n;
-            }
-            last = *n;
-        }
-        (
-            res,
-            if zeros == line.len() - 1 { 0 } else { 1 },
-            last,
-            first,
-        )
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Vec<Vec<i64>>;
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError> {
+        contents
+            .split("\n")
+            .enumerate()
+            .map(|(i, l)| {
+                l.split_whitespace()
+                    .map(|n| {
+                        n.parse()
+                            .map_err(|_| InputError::at_line(i + 1, format!("not a number: {}", n)))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        input
+            .iter()
+            .map(|row| solve(row.clone()).1)
+            .sum::<i64>()
+            .into()
     }
 
-    fn solve(nums: Vec<i64>) -> (i64, i64) {
-        println!("{:?}", nums);
+    fn part2(input: &Self::Input) -> Answer {
+        input
+            .iter()
+            .map(|row| solve(row.clone()).0)
+            .sum::<i64>()
+            .into()
+    }
+}
+
+fn find_pattern(line: Vec<i64>) -> (Vec<i64>, i64, i64, i64) {
+    let mut res = Vec::new();
+    let mut prev = 0;
+    let mut zeros = 0;
+    let mut last = 0;
+    let mut first = 0;
+    for (i, n) in line.iter().enumerate() {
+        if i == 0 {
+            prev = *n;
+            first = *n;
+        } else {
+            res.push(n - prev);
+            zeros += if (n - prev) == 0 { 1 } else { 0 };
+            prev = *n;
+        }
+        last = *n;
+    }
+    (
+        res,
+        if zeros == line.len() - 1 { 0 } else { 1 },
+        last,
+        first,
+    )
+}
 
-        let (mut p, mut sum, mut last, mut first) = find_pattern(nums);
+fn solve(nums: Vec<i64>) -> (i64, i64) {
+    let (mut p, mut sum, mut last, mut first) = find_pattern(nums);
 
-        let mut spaces = 1;
-        let mut sp = vec!["  "; spaces].join(" ");
-        println!("{}{:?}", sp, p);
+    let mut lasts: Vec<_> = Vec::new();
+    let mut firsts: Vec<_> = Vec::new();
+    lasts.push(last);
+    firsts.push(first);
 
-        let mut lasts: Vec<_> = Vec::new();
-        let mut firsts: Vec<_> = Vec::new();
+    while sum != 0 {
+        (p, sum, last, first) = find_pattern(p);
         lasts.push(last);
         firsts.push(first);
-        // let mut last_p = p;
-
-        while sum != 0 {
-            (p, sum, last, first) = find_pattern(p);
-            spaces += 1;
-            sp = vec!["  "; spaces].join(" ");
-            println!("{}{:?}", sp, p);
-            lasts.push(last);
-            firsts.push(first);
-        }
-
-        let mut sum_firsts = 0;
-        while let Some(n) = firsts.pop() {
-            sum_firsts = n - sum_firsts;
-        }
+    }
 
-        let mut sum_lasts = 0;
-        while let Some(n) = lasts.pop() {
-            sum_lasts += n;
-        }
+    let mut sum_firsts = 0;
+    while let Some(n) = firsts.pop() {
+        sum_firsts = n - sum_firsts;
+    }
 
-        (sum_firsts, sum_lasts)
+    let mut sum_lasts = 0;
+    while let Some(n) = lasts.pop() {
+        sum_lasts += n;
     }
 
+    (sum_firsts, sum_lasts)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::solve;
+
     #[test]
     fn p1_and_2() {
         let contents = fs::read_to_string("./problems/d9.txt").expect("");
@@ -0,0 +1,115 @@
+//! Graphviz DOT output, to look at the shape of an input with
+//! `dot -Tsvg graph.dot > graph.svg`.
+
+#![allow(dead_code)]
+
+use std::fmt::Display;
+
+/// A graph written out statement by statement. Node ids and attribute values
+/// are quoted, so they can be anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dot {
+    directed: bool,
+    name: String,
+    statements: Vec<String>,
+}
+
+impl Dot {
+    pub fn digraph(name: &str) -> Self {
+        Self {
+            directed: true,
+            name: name.to_string(),
+            statements: Vec::new(),
+        }
+    }
+
+    pub fn graph(name: &str) -> Self {
+        Self {
+            directed: false,
+            ..Self::digraph(name)
+        }
+    }
+
+    /// An attribute of the whole graph, like `rankdir = LR`.
+    pub fn attr(&mut self, key: &str, value: &str) -> &mut Self {
+        self.statements.push(format!("{} = {}", key, quote(value)));
+        self
+    }
+
+    /// Attributes every node gets unless it sets its own.
+    pub fn node_defaults(&mut self, attrs: &[(&str, &str)]) -> &mut Self {
+        self.statements.push(format!("node{}", attr_list(attrs)));
+        self
+    }
+
+    pub fn node(&mut self, id: &str, attrs: &[(&str, &str)]) -> &mut Self {
+        self.statements
+            .push(format!("{}{}", quote(id), attr_list(attrs)));
+        self
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str, attrs: &[(&str, &str)]) -> &mut Self {
+        let arrow = if self.directed { "->" } else { "--" };
+        self.statements.push(format!(
+            "{} {} {}{}",
+            quote(from),
+            arrow,
+            quote(to),
+            attr_list(attrs)
+        ));
+        self
+    }
+}
+
+impl Display for Dot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = if self.directed { "digraph" } else { "graph" };
+        writeln!(f, "{} {} {{", kind, quote(&self.name))?;
+        for statement in &self.statements {
+            writeln!(f, "    {};", statement)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn attr_list(attrs: &[(&str, &str)]) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+    let attrs = attrs
+        .iter()
+        .map(|(k, v)| format!("{} = {}", k, quote(v)))
+        .collect::<Vec<_>>();
+    format!(" [{}]", attrs.join(", "))
+}
+
+#[cfg(test)]
+mod test {
+    use super::Dot;
+
+    #[test]
+    fn test_dot() {
+        let mut dot = Dot::digraph("g");
+        dot.attr("rankdir", "LR")
+            .node("a", &[("shape", "box")])
+            .node("say \"hi\"", &[])
+            .edge("a", "say \"hi\"", &[("label", "3"), ("color", "red")]);
+        assert_eq!(
+            dot.to_string(),
+            r#"digraph "g" {
+    rankdir = "LR";
+    "a" [shape = "box"];
+    "say \"hi\"";
+    "a" -> "say \"hi\"" [label = "3", color = "red"];
+}"#
+        );
+
+        let mut dot = Dot::graph("u");
+        dot.edge("a", "b", &[]);
+        assert_eq!(dot.to_string(), "graph \"u\" {\n    \"a\" -- \"b\";\n}");
+    }
+}
@@ -0,0 +1,557 @@
+//! Undirected graphs with weighted edges, for the puzzles that are about how
+//! things are wired together rather than where they are.
+
+#![allow(dead_code)]
+
+use std::{
+    collections::{BinaryHeap, HashMap},
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+use crate::{
+    dot::Dot,
+    grid::Grid,
+    input::InputError,
+    parsing::pair,
+    point::{Direction, Point},
+};
+
+/// Nodes are numbered in the order they're added, and each keeps its `N` label.
+#[derive(Clone, Debug)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    adjacency: Vec<HashMap<usize, u64>>,
+}
+
+/// A way to split the graph in two, and what it takes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cut {
+    /// Total weight of the edges going across
+    pub weight: u64,
+    /// The nodes on the smaller side, the others are on the other side
+    pub side: Vec<usize>,
+    /// The edges going across, as pairs of nodes
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Cut {
+    /// How many nodes are on each side, `side` first.
+    pub fn sizes(&self, graph_len: usize) -> (usize, usize) {
+        (self.side.len(), graph_len - self.side.len())
+    }
+}
+
+impl<N: Eq + Hash + Clone> Graph<N> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            adjacency: Vec::new(),
+        }
+    }
+
+    /// The number of `node`, adding it if it's new.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&i) = self.index.get(&node) {
+            return i;
+        }
+        self.nodes.push(node.clone());
+        self.adjacency.push(HashMap::new());
+        self.index.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    /// Connects both nodes, adding them if they're new. Adding the same edge
+    /// again adds up the weights.
+    pub fn add_edge(&mut self, a: N, b: N, weight: u64) {
+        let a = self.add_node(a);
+        let b = self.add_node(b);
+        *self.adjacency[a].entry(b).or_default() += weight;
+        *self.adjacency[b].entry(a).or_default() += weight;
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, i: usize) -> &N {
+        &self.nodes[i]
+    }
+
+    pub fn index_of(&self, node: &N) -> Option<usize> {
+        self.index.get(node).copied()
+    }
+
+    /// The nodes connected to `i`, with the weight of the edge.
+    pub fn neighbours(&self, i: usize) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.adjacency[i].iter().map(|(&j, &w)| (j, w))
+    }
+
+    pub fn weight(&self, a: usize, b: usize) -> Option<u64> {
+        self.adjacency[a].get(&b).copied()
+    }
+
+    /// Every edge once, with the smaller node first.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, u64)> + '_ {
+        self.adjacency.iter().enumerate().flat_map(|(a, adj)| {
+            adj.iter()
+                .filter(move |(&b, _)| a < b)
+                .map(move |(&b, &w)| (a, b, w))
+        })
+    }
+
+    /// The cut with the lowest weight, using Stoer-Wagner. `None` for graphs
+    /// with fewer than two nodes, which can't be split.
+    pub fn min_cut(&self) -> Option<Cut> {
+        if self.len() < 2 {
+            return None;
+        }
+
+        // Nodes get merged together, each one remembers the original ones in it
+        let mut adjacency = self.adjacency.clone();
+        let mut members: Vec<Vec<usize>> = (0..self.len()).map(|i| vec![i]).collect();
+        let mut active: Vec<usize> = (0..self.len()).collect();
+        let mut best: Option<(u64, Vec<usize>)> = None;
+
+        while active.len() > 1 {
+            let (s, t, weight) = most_tightly_connected(&adjacency, &active);
+            if best.as_ref().is_none_or(|(w, _)| weight < *w) {
+                best = Some((weight, members[t].clone()));
+            }
+
+            // Merge t into s
+            let t_edges = std::mem::take(&mut adjacency[t]);
+            for (n, w) in t_edges {
+                adjacency[n].remove(&t);
+                if n != s {
+                    *adjacency[s].entry(n).or_default() += w;
+                    *adjacency[n].entry(s).or_default() += w;
+                }
+            }
+            let t_members = std::mem::take(&mut members[t]);
+            members[s].extend(t_members);
+            active.retain(|&n| n != t);
+        }
+
+        let (weight, side) = best?;
+        let mut in_side = vec![false; self.len()];
+        for &n in &side {
+            in_side[n] = true;
+        }
+        if side.len() * 2 > self.len() {
+            in_side.iter_mut().for_each(|b| *b = !*b);
+        }
+        let side = (0..self.len()).filter(|&n| in_side[n]).collect();
+        let edges = self
+            .edges()
+            .filter(|(a, b, _)| in_side[*a] != in_side[*b])
+            .map(|(a, b, _)| (a, b))
+            .collect();
+
+        Some(Cut {
+            weight,
+            side,
+            edges,
+        })
+    }
+
+    /// Groups of nodes connected to each other, leaving out the `removed`
+    /// edges.
+    pub fn components(&self, removed: &[(usize, usize)]) -> Vec<Vec<usize>> {
+        let is_removed = |a: usize, b: usize| {
+            removed
+                .iter()
+                .any(|&(x, y)| (x, y) == (a, b) || (x, y) == (b, a))
+        };
+
+        let mut seen = vec![false; self.len()];
+        let mut res = Vec::new();
+        for start in 0..self.len() {
+            if seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut component = Vec::new();
+            let mut stack = vec![start];
+            while let Some(n) = stack.pop() {
+                component.push(n);
+                for (next, _) in self.neighbours(n) {
+                    if !seen[next] && !is_removed(n, next) {
+                        seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+            component.sort();
+            res.push(component);
+        }
+        res
+    }
+}
+
+impl<N: Eq + Hash + Clone> Default for Graph<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Eq + Hash + Clone + Display> Graph<N> {
+    /// Nodes labelled with their `N`, edges with their weight unless it's 1.
+    pub fn to_dot(&self) -> Dot {
+        let mut dot = Dot::graph("graph");
+        for (i, node) in self.nodes.iter().enumerate() {
+            dot.node(&i.to_string(), &[("label", &node.to_string())]);
+        }
+        for (a, b, weight) in self.edges() {
+            let label = weight.to_string();
+            let attrs: &[(&str, &str)] = if weight == 1 {
+                &[]
+            } else {
+                &[("label", &label)]
+            };
+            dot.edge(&a.to_string(), &b.to_string(), attrs);
+        }
+        dot
+    }
+}
+
+impl Graph<String> {
+    /// One `a: b c d` line per node, listing some of the nodes it's connected
+    /// to. Every edge has a weight of 1.
+    pub fn parse_adjacency(contents: &str) -> Result<Self, InputError> {
+        let mut graph = Self::new();
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (from, to) = pair(line, ":").map_err(|e| e.on_line(i + 1))?;
+            graph.add_node(from.to_string());
+            for other in to.split_whitespace() {
+                graph.add_edge(from.to_string(), other.to_string(), 1);
+            }
+        }
+        Ok(graph)
+    }
+}
+
+/// A maze on a grid reduced to its junctions, the cells where the paths split,
+/// plus a start and an end. Corridors between junctions become edges weighted
+/// with their length, one-way if they can only be walked one way.
+#[derive(Clone, Debug)]
+pub struct JunctionGraph {
+    pub junctions: Vec<Point>,
+    /// The junctions reachable from each one through a single corridor, with
+    /// the length of the longest such corridor
+    pub edges: Vec<Vec<(usize, u32)>>,
+}
+
+impl JunctionGraph {
+    /// `open` says which cells are part of the maze, and `can_move` whether a
+    /// cell can be left in a direction. `start` and `end` are always junctions
+    /// 0 and 1, the other junctions follow in grid order.
+    pub fn contract<T: Eq + Debug + Hash + Copy>(
+        grid: &Grid<T>,
+        start: Point,
+        end: Point,
+        open: impl Fn(&T) -> bool,
+        can_move: impl Fn(Point, Direction) -> bool,
+    ) -> Self {
+        let open = &open;
+        let open_dirs = |p: Point| {
+            Direction::ALL
+                .into_iter()
+                .filter(move |&d| grid.step(p, d).is_some_and(|next| open(&grid[next])))
+        };
+
+        let mut junctions = vec![start, end];
+        junctions.extend(
+            grid.points()
+                .filter(|&p| p != start && p != end && open(&grid[p]) && open_dirs(p).count() > 2),
+        );
+        let index: HashMap<Point, usize> =
+            junctions.iter().enumerate().map(|(i, p)| (*p, i)).collect();
+
+        let mut edges = vec![Vec::new(); junctions.len()];
+        for (from, &junction) in junctions.iter().enumerate() {
+            for dir in open_dirs(junction).filter(|&d| can_move(junction, d)) {
+                // Follows the corridor, which has a single way on, to its end
+                let mut prev = junction;
+                let mut pos = junction.step(dir);
+                let mut len = 1;
+                let to = loop {
+                    if let Some(&to) = index.get(&pos) {
+                        break Some(to);
+                    }
+                    let next = open_dirs(pos)
+                        .filter(|&d| can_move(pos, d))
+                        .map(|d| pos.step(d))
+                        .find(|&next| next != prev);
+                    match next {
+                        Some(next) => (prev, pos, len) = (pos, next, len + 1),
+                        None => break None,
+                    }
+                };
+
+                let Some(to) = to.filter(|&to| to != from) else {
+                    continue;
+                };
+                let edges: &mut Vec<(usize, u32)> = &mut edges[from];
+                match edges.iter_mut().find(|(n, _)| *n == to) {
+                    Some((_, w)) => *w = (*w).max(len),
+                    None => edges.push((to, len)),
+                }
+            }
+        }
+
+        Self { junctions, edges }
+    }
+
+    pub fn index_of(&self, p: Point) -> Option<usize> {
+        self.junctions.iter().position(|&j| j == p)
+    }
+
+    /// Length of the longest path from `from` to `to` that doesn't go through
+    /// any junction twice, if there's a path at all.
+    pub fn longest_path(&self, from: usize, to: usize) -> Option<u32> {
+        assert!(
+            self.junctions.len() <= 64,
+            "too many junctions to keep track of the visited ones"
+        );
+
+        // When a single junction leads to the end, going anywhere else from
+        // there can't get to the end anymore
+        let before_end = (0..self.edges.len())
+            .filter(|&n| self.edges[n].iter().any(|(next, _)| *next == to))
+            .collect::<Vec<_>>();
+        let last = match before_end[..] {
+            [last] if last != from => Some(last),
+            _ => None,
+        };
+        self.dfs(from, to, last, 0)
+    }
+
+    fn dfs(&self, node: usize, to: usize, last: Option<usize>, visited: u64) -> Option<u32> {
+        if node == to {
+            return Some(0);
+        }
+        let visited = visited | 1 << node;
+
+        self.edges[node]
+            .iter()
+            .filter(|(next, _)| visited & 1 << next == 0)
+            .filter(|(next, _)| Some(node) != last || *next == to)
+            .filter_map(|&(next, len)| Some(self.dfs(next, to, last, visited)? + len))
+            .max()
+    }
+
+    /// The junctions labelled with where they are, the start and end stand
+    /// out. Corridors that can be walked both ways are drawn without arrows.
+    pub fn to_dot(&self) -> Dot {
+        let mut dot = Dot::digraph("junctions");
+        for (i, junction) in self.junctions.iter().enumerate() {
+            let shape = if i < 2 { "doublecircle" } else { "circle" };
+            dot.node(
+                &i.to_string(),
+                &[("label", &junction.to_string()), ("shape", shape)],
+            );
+        }
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, len) in edges {
+                let back = self.edges[to].contains(&(from, len));
+                if back && to < from {
+                    continue;
+                }
+                let dir = if back { "none" } else { "forward" };
+                dot.edge(
+                    &from.to_string(),
+                    &to.to_string(),
+                    &[("label", &len.to_string()), ("dir", dir)],
+                );
+            }
+        }
+        dot
+    }
+}
+
+/// One phase of Stoer-Wagner: adds the active nodes one by one, always the
+/// one most tightly connected to the ones already added. The last two added
+/// are returned, with the weight between the last one and all the others.
+fn most_tightly_connected(
+    adjacency: &[HashMap<usize, u64>],
+    active: &[usize],
+) -> (usize, usize, u64) {
+    let mut added = vec![false; adjacency.len()];
+    let mut weights = vec![0u64; adjacency.len()];
+    let mut heap: BinaryHeap<(u64, usize)> = BinaryHeap::new();
+    let mut order = Vec::with_capacity(active.len());
+
+    while order.len() < active.len() {
+        // Nodes not connected to the added ones come last, in any order
+        let next = loop {
+            match heap.pop() {
+                Some((w, n)) if !added[n] && w == weights[n] => break n,
+                Some(_) => continue,
+                None => break *active.iter().find(|&&n| !added[n]).unwrap(),
+            }
+        };
+
+        added[next] = true;
+        order.push(next);
+        for (&n, &w) in &adjacency[next] {
+            if !added[n] {
+                weights[n] += w;
+                heap.push((weights[n], n));
+            }
+        }
+    }
+
+    let t = order[order.len() - 1];
+    let s = order[order.len() - 2];
+    (s, t, weights[t])
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        grid::Grid,
+        point::{Direction, Point},
+    };
+
+    use super::{Graph, JunctionGraph};
+
+    const EXAMPLE: &str = "\
+jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+    #[test]
+    fn test_parse() {
+        let graph = Graph::parse_adjacency(EXAMPLE).unwrap();
+        assert_eq!(graph.len(), 15);
+        assert_eq!(graph.edges().count(), 33);
+
+        let jqt = graph.index_of(&"jqt".to_string()).unwrap();
+        let ntq = graph.index_of(&"ntq".to_string()).unwrap();
+        assert_eq!(graph.weight(ntq, jqt), Some(1));
+        assert_eq!(graph.neighbours(jqt).count(), 4);
+    }
+
+    #[test]
+    fn test_min_cut() {
+        let graph = Graph::parse_adjacency(EXAMPLE).unwrap();
+        let cut = graph.min_cut().unwrap();
+
+        assert_eq!(cut.weight, 3);
+        let mut names = cut
+            .edges
+            .iter()
+            .map(|&(a, b)| {
+                let mut pair = [graph.node(a).as_str(), graph.node(b).as_str()];
+                pair.sort();
+                pair
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, [["bvb", "cmg"], ["hfx", "pzl"], ["jqt", "nvd"]]);
+
+        let (a, b) = cut.sizes(graph.len());
+        assert_eq!(a * b, 54);
+        assert_eq!(graph.components(&cut.edges).len(), 2);
+    }
+
+    #[test]
+    fn test_weighted() {
+        // A heavy triangle and a light edge to a fourth node
+        let mut graph = Graph::new();
+        graph.add_edge('a', 'b', 5);
+        graph.add_edge('b', 'c', 5);
+        graph.add_edge('c', 'a', 5);
+        graph.add_edge('c', 'd', 2);
+        graph.add_edge('d', 'c', 1);
+
+        let cut = graph.min_cut().unwrap();
+        assert_eq!(cut.weight, 3);
+        assert_eq!(cut.side, [graph.index_of(&'d').unwrap()]);
+
+        let dot = graph.to_dot().to_string();
+        assert!(dot.starts_with("graph "));
+        assert!(dot.contains("\"3\" [label = \"d\"]"));
+        assert!(dot.contains("\"2\" -- \"3\" [label = \"3\"]"));
+        assert_eq!(dot.matches(" -- ").count(), 4);
+
+        let mut lonely = Graph::new();
+        lonely.add_node(1);
+        assert_eq!(lonely.min_cut(), None);
+        lonely.add_node(2);
+        assert_eq!(lonely.min_cut().unwrap().weight, 0);
+    }
+
+    #[test]
+    fn test_junctions() {
+        // Two ways around the block in the middle, the longer one with a slope
+        let maze: Grid<char> = "\
+#.#####
+#.....#
+#.###v#
+#.#...#
+#.#.###
+#.....#
+#####.#"
+            .parse()
+            .unwrap();
+        let start = Point::new(1, 0);
+        let end = Point::new(5, 6);
+        let open = |c: &char| *c != '#';
+
+        let both_ways = JunctionGraph::contract(&maze, start, end, open, |_, _| true);
+        assert_eq!(both_ways.junctions.len(), 4);
+        let (s, e) = (0, 1);
+        let split = both_ways.index_of(Point::new(1, 1)).unwrap();
+        let join = both_ways.index_of(Point::new(3, 5)).unwrap();
+        assert_eq!(both_ways.edges[s], [(split, 1)]);
+        // The longest of the two corridors between the same junctions
+        assert!(both_ways.edges[join].contains(&(split, 10)));
+        assert_eq!(both_ways.longest_path(s, e), Some(14));
+        assert_eq!(both_ways.longest_path(e, s), Some(14));
+
+        // The slope can't be walked back up
+        let slippery = JunctionGraph::contract(&maze, start, end, open, |p, d| {
+            Direction::from_char(maze[p]).is_none_or(|slope| slope == d)
+        });
+        assert!(slippery.edges[split].contains(&(join, 10)));
+        assert!(slippery.edges[join].contains(&(split, 6)));
+        assert_eq!(slippery.longest_path(s, e), Some(14));
+        assert_eq!(slippery.longest_path(e, s), Some(10));
+
+        let dot = slippery.to_dot().to_string();
+        assert!(dot.contains("\"0\" [label = \"(1, 0)\", shape = \"doublecircle\"]"));
+        let (split, join) = (split.to_string(), join.to_string());
+        let down = format!(
+            "\"{}\" -> \"{}\" [label = \"10\", dir = \"forward\"]",
+            split, join
+        );
+        assert!(dot.contains(&down));
+        // The start's corridor goes both ways and is only drawn once
+        assert!(dot.contains(&format!(
+            "\"0\" -> \"{}\" [label = \"1\", dir = \"none\"]",
+            split
+        )));
+        assert_eq!(dot.matches(" -> ").count(), 4);
+    }
+}
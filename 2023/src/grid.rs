@@ -5,9 +5,32 @@ use std::{
     collections::HashSet,
     fmt::{Debug, Display},
     fs,
+    ops::{Index, IndexMut},
+    str::FromStr,
 };
 
-#[derive(Clone)]
+use crate::{
+    input::InputError,
+    parsing,
+    point::{Direction, Point},
+};
+
+/// Offsets (x, y) of the left, right, down and up neighbours, in that order.
+pub const ORTHOGONAL: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, 1), (0, -1)];
+
+/// Offsets of all eight neighbours, diagonals included (the Moore neighbourhood).
+pub const MOORE: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+#[derive(Clone, Debug)]
 pub struct Grid<T> {
     pub _grid: Vec<Vec<T>>,
 }
@@ -23,6 +46,24 @@ impl<T: Eq + Debug + Hash + Clone + Copy> Grid<T> {
         Self { _grid: grid }
     }
 
+    /// Grid of the puzzle text, with `f` turning every character into a cell.
+    /// Characters that `f` maps to `None` are an error, so are rows of
+    /// different lengths. Trailing newlines are ignored.
+    pub fn parse_with(
+        contents: &str,
+        f: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, InputError> {
+        Ok(Self::new(parsing::grid(contents, f)?))
+    }
+
+    /// Grid of single digits, like the heat loss map of day 17.
+    pub fn from_digits(contents: &str) -> Result<Self, InputError>
+    where
+        T: From<u8>,
+    {
+        Ok(Self::new(parsing::digit_grid(contents)?))
+    }
+
     pub fn contains(&self, x: i32, y: i32) -> bool {
         self.get(x, y).is_some()
     }
@@ -33,9 +74,8 @@ impl<T: Eq + Debug + Hash + Clone + Copy> Grid<T> {
         y: i32,
         cs: &HashSet<T>,
     ) -> Vec<(i32, i32, &T)> {
-        let next: Vec<(i32, i32, &T)> = self.xy_directions(x, y);
-        next.iter()
-            .filter_map(|d| if cs.contains(d.2) { Some(*d) } else { None })
+        self.orthogonal_neighbours(x, y)
+            .filter(|(_, _, c)| cs.contains(c))
             .collect::<Vec<_>>()
     }
 
@@ -56,12 +96,9 @@ impl<T: Eq + Debug + Hash + Clone + Copy> Grid<T> {
             return false;
         }
 
-        println!("first={:?}", first);
-
         let mut next = first.clone();
-        while !next.is_empty() {
-            let (x, y, _) = next.pop().unwrap();
-            self.set(x, y, used_flag.clone());
+        while let Some((x, y, _)) = next.pop() {
+            self.set(x, y, used_flag);
 
             for n in self
                 .get_xy_directions_with_match(x, y, &cs)
@@ -72,7 +109,7 @@ impl<T: Eq + Debug + Hash + Clone + Copy> Grid<T> {
                 next.push(n);
             }
         }
-        return true;
+        true
     }
 
     pub fn get(&self, x: i32, y: i32) -> Option<&T> {
@@ -82,8 +119,7 @@ impl<T: Eq + Debug + Hash + Clone + Copy> Grid<T> {
 
         self._grid
             .get(y as usize)
-            .map(|row| row.get(x as usize).map(|c| c))
-            .flatten()
+            .and_then(|row| row.get(x as usize))
     }
 
     pub fn transpose(&self) -> Vec<Vec<T>>
@@ -93,7 +129,7 @@ impl<T: Eq + Debug + Hash + Clone + Copy> Grid<T> {
         let v = self._grid.clone();
         assert!(!v.is_empty());
         (0..v[0].len())
-            .map(|i| v.iter().map(|inner| inner[i].clone()).collect::<Vec<T>>())
+            .map(|i| v.iter().map(|inner| inner[i]).collect::<Vec<T>>())
             .collect()
     }
 
@@ -108,8 +144,7 @@ impl<T: Eq + Debug + Hash + Clone + Copy> Grid<T> {
 
         self._grid
             .get(y as usize)
-            .map(|row| row.get(x as usize).map(|c| (x, y, c)))
-            .flatten()
+            .and_then(|row| row.get(x as usize).map(|c| (x, y, c)))
     }
 
     pub fn find(&self, val: &T) -> Option<(i32, i32)> {
@@ -120,7 +155,7 @@ impl<T: Eq + Debug + Hash + Clone + Copy> Grid<T> {
                 }
             }
         }
-        return None;
+        None
     }
 
     pub fn find_all(&self, val: &T) -> HashSet<(i32, i32)> {
@@ -132,19 +167,65 @@ impl<T: Eq + Debug + Hash + Clone + Copy> Grid<T> {
                 }
             }
         }
-        return res;
+        res
     }
 
     pub fn xy_directions(&self, x: i32, y: i32) -> Vec<(i32, i32, &T)> {
-        vec![
-            self.get_with_coordinates(x - 1, y),
-            self.get_with_coordinates(x + 1, y),
-            self.get_with_coordinates(x, y + 1),
-            self.get_with_coordinates(x, y - 1),
-        ]
-        .iter()
-        .filter_map(|el| *el)
-        .collect::<Vec<_>>()
+        self.orthogonal_neighbours(x, y).collect()
+    }
+
+    /// Cells at each of the `offsets` from (x, y) that are inside the grid.
+    pub fn neighbours<'a>(
+        &'a self,
+        x: i32,
+        y: i32,
+        offsets: &'a [(i32, i32)],
+    ) -> impl Iterator<Item = (i32, i32, &'a T)> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |(dx, dy)| self.get_with_coordinates(x + dx, y + dy))
+    }
+
+    pub fn orthogonal_neighbours(&self, x: i32, y: i32) -> impl Iterator<Item = (i32, i32, &T)> {
+        self.neighbours(x, y, &ORTHOGONAL)
+    }
+
+    pub fn moore_neighbours(&self, x: i32, y: i32) -> impl Iterator<Item = (i32, i32, &T)> {
+        self.neighbours(x, y, &MOORE)
+    }
+
+    pub fn get_at(&self, p: Point) -> Option<&T> {
+        self.get(p.x, p.y)
+    }
+
+    pub fn set_at(&mut self, p: Point, val: T) -> bool {
+        self.set(p.x, p.y, val)
+    }
+
+    pub fn contains_point(&self, p: Point) -> bool {
+        self.get_at(p).is_some()
+    }
+
+    /// The next point going in `dir` from `p`, if it's inside the grid.
+    pub fn step(&self, p: Point, dir: Direction) -> Option<Point> {
+        Some(p.step(dir)).filter(|next| self.contains_point(*next))
+    }
+
+    /// Up, right, down and left of `p`, skipping the ones outside the grid.
+    pub fn neighbours_at(&self, p: Point) -> impl Iterator<Item = (Point, &T)> {
+        p.orthogonal()
+            .filter_map(move |next| self.get_at(next).map(|c| (next, c)))
+    }
+
+    /// Every point of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let cols = self.cols();
+        (0..self.rows()).flat_map(move |y| (0..cols).map(move |x| Point::new(x, y)))
+    }
+
+    /// The grid repeated forever in every direction.
+    pub fn tiled(&self) -> Tiled<'_, T> {
+        Tiled { grid: self }
     }
 
     pub fn print(&self)
@@ -159,7 +240,7 @@ impl<T: Eq + Debug + Hash + Clone + Copy> Grid<T> {
                 .join("");
             println!("{:?}", s);
         }
-        println!("")
+        println!()
     }
 
     pub fn save_to_file(&self, path: &str) {
@@ -181,20 +262,97 @@ impl<T: Eq + Debug + Hash + Clone + Copy> Grid<T> {
     }
 }
 
+/// A view of a grid repeated forever in every direction, so any coordinate
+/// has a cell. The tile at (0, 0) is the grid itself.
+#[derive(Clone, Copy, Debug)]
+pub struct Tiled<'a, T> {
+    grid: &'a Grid<T>,
+}
+
+impl<'a, T> Tiled<'a, T> {
+    fn size(&self) -> (i64, i64) {
+        (
+            self.grid._grid[0].len() as i64,
+            self.grid._grid.len() as i64,
+        )
+    }
+
+    /// Where (x, y) falls inside its tile.
+    pub fn wrap(&self, x: i64, y: i64) -> (i32, i32) {
+        let (cols, rows) = self.size();
+        (x.rem_euclid(cols) as i32, y.rem_euclid(rows) as i32)
+    }
+
+    /// Which copy of the grid (x, y) falls in, counting from the original one.
+    pub fn tile_of(&self, x: i64, y: i64) -> (i64, i64) {
+        let (cols, rows) = self.size();
+        (x.div_euclid(cols), y.div_euclid(rows))
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> &'a T {
+        let (x, y) = self.wrap(x, y);
+        &self.grid._grid[y as usize][x as usize]
+    }
+
+    /// Cells at each of the `offsets` from (x, y).
+    pub fn neighbours(
+        self,
+        x: i64,
+        y: i64,
+        offsets: &'a [(i32, i32)],
+    ) -> impl Iterator<Item = (i64, i64, &'a T)> + 'a {
+        offsets.iter().map(move |(dx, dy)| {
+            let (nx, ny) = (x + *dx as i64, y + *dy as i64);
+            (nx, ny, self.get(nx, ny))
+        })
+    }
+
+    pub fn orthogonal_neighbours(
+        self,
+        x: i64,
+        y: i64,
+    ) -> impl Iterator<Item = (i64, i64, &'a T)> + 'a {
+        self.neighbours(x, y, &ORTHOGONAL)
+    }
+
+    pub fn moore_neighbours(self, x: i64, y: i64) -> impl Iterator<Item = (i64, i64, &'a T)> + 'a {
+        self.neighbours(x, y, &MOORE)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        &self._grid[p.y as usize][p.x as usize]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        &mut self._grid[p.y as usize][p.x as usize]
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = InputError;
+
+    fn from_str(contents: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(parsing::char_grid(contents)?))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::fs;
 
     use super::Grid;
+    use crate::point::{Direction, Point};
 
     #[test]
     fn test_get() {
-        let contents = fs::read_to_string("./problems/d10_sample.txt").expect("");
-        let g: Vec<Vec<char>> = contents
-            .split("\n")
-            .map(|l| l.chars().collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        let grid = Grid::new(g);
+        let contents = fs::read_to_string("./problems/d10_sample1.txt").expect("");
+        let grid: Grid<char> = contents.parse().unwrap();
 
         assert_eq!(grid.get(0, 0).unwrap(), &'.');
         assert_eq!(grid.get(0, 2).unwrap(), &'S');
@@ -204,12 +362,8 @@ mod test {
 
     #[test]
     fn test_xy_directions() {
-        let contents = fs::read_to_string("./problems/d10_sample.txt").expect("");
-        let g: Vec<Vec<char>> = contents
-            .split("\n")
-            .map(|l| l.chars().collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        let grid = Grid::new(g);
+        let contents = fs::read_to_string("./problems/d10_sample1.txt").expect("");
+        let grid: Grid<char> = contents.parse().unwrap();
 
         let dirs1 = grid.xy_directions(0, 0);
         println!("{:?}", dirs1);
@@ -217,16 +371,91 @@ mod test {
 
     #[test]
     fn test_update() {
-        let contents = fs::read_to_string("./problems/d10_sample.txt").expect("");
-        let g: Vec<Vec<char>> = contents
-            .split("\n")
-            .map(|l| l.chars().collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        let mut grid = Grid::new(g);
+        let contents = fs::read_to_string("./problems/d10_sample1.txt").expect("");
+        let mut grid: Grid<char> = contents.parse().unwrap();
 
         grid.set(0, 0, 'X');
 
         assert_eq!(grid.get(0, 0).unwrap(), &'X');
         assert_eq!(grid.get(0, 2).unwrap(), &'S');
     }
+
+    #[test]
+    fn test_parse() {
+        let grid: Grid<char> = "#.\n.#\n".parse().unwrap();
+        assert_eq!((grid.cols(), grid.rows()), (2, 2));
+        assert_eq!(grid.get(1, 1), Some(&'#'));
+
+        let grid = Grid::<u32>::from_digits("12\n34\n\n").unwrap();
+        assert_eq!(grid.get(0, 1), Some(&3));
+
+        let grid = Grid::parse_with("#.\n.#", |c| Some(c == '#')).unwrap();
+        assert_eq!(grid._grid, vec![vec![true, false], vec![false, true]]);
+
+        assert_eq!(
+            "##\n#\n".parse::<Grid<char>>().unwrap_err().to_string(),
+            "line 2: row has 1 cells, expected 2"
+        );
+        assert_eq!(
+            Grid::<u32>::from_digits("12\n3.").unwrap_err().to_string(),
+            "line 2, column 2: unexpected `.`"
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid: Grid<char> = "abc\ndef\nghi".parse().unwrap();
+        let cells = |it: &mut dyn Iterator<Item = (i32, i32, &char)>| {
+            it.map(|(_, _, c)| *c).collect::<String>()
+        };
+
+        assert_eq!(cells(&mut grid.orthogonal_neighbours(1, 1)), "dfhb");
+        assert_eq!(cells(&mut grid.moore_neighbours(1, 1)), "abcdfghi");
+        assert_eq!(cells(&mut grid.moore_neighbours(0, 0)), "bde");
+
+        let knight = [(1, 2), (2, 1), (-1, 2)];
+        assert_eq!(
+            grid.neighbours(0, 0, &knight).collect::<Vec<_>>(),
+            vec![(1, 2, &'h'), (2, 1, &'f')]
+        );
+    }
+
+    #[test]
+    fn test_points() {
+        let mut grid: Grid<char> = "abc\ndef".parse().unwrap();
+        let p = Point::new(2, 0);
+
+        assert_eq!(grid.get_at(p), Some(&'c'));
+        assert_eq!(grid[Point::new(0, 1)], 'd');
+        assert_eq!(grid.step(p, Direction::Down), Some(Point::new(2, 1)));
+        assert_eq!(grid.step(p, Direction::Right), None);
+        assert_eq!(
+            grid.neighbours_at(p).map(|(_, c)| *c).collect::<String>(),
+            "fb"
+        );
+        assert_eq!(grid.points().count(), 6);
+
+        grid[p] = 'x';
+        assert!(grid.set_at(Point::new(0, 0), 'y'));
+        assert!(!grid.set_at(Point::new(3, 0), 'z'));
+        assert_eq!(grid._grid[0], vec!['y', 'b', 'x']);
+    }
+
+    #[test]
+    fn test_tiled() {
+        let grid: Grid<char> = "ab\ncd\nef".parse().unwrap();
+        let tiled = grid.tiled();
+
+        assert_eq!(tiled.get(1, 2), &'f');
+        assert_eq!(tiled.get(3, 3), &'b');
+        assert_eq!(tiled.get(-1, -1), &'f');
+        assert_eq!(tiled.wrap(-3, 7), (1, 1));
+        assert_eq!(tiled.tile_of(-3, 7), (-2, 2));
+        assert_eq!(tiled.tile_of(1, 2), (0, 0));
+        assert_eq!(
+            tiled.orthogonal_neighbours(0, 0).collect::<Vec<_>>(),
+            vec![(-1, 0, &'b'), (1, 0, &'b'), (0, 1, &'c'), (0, -1, &'e')]
+        );
+        assert_eq!(tiled.moore_neighbours(-5, 4).count(), 8);
+    }
 }
@@ -0,0 +1,197 @@
+use std::{error::Error, fmt::Display, fs, path::Path};
+
+pub const PROBLEMS_DIR: &str = "./problems";
+
+/// Which input of a day to run: the real puzzle input (`dN.txt`) or one of the
+/// worked examples from the puzzle text (`dN_sampleK.txt`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InputKind {
+    Real,
+    Sample(u8),
+}
+
+impl Display for InputKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputKind::Real => f.pad("real"),
+            InputKind::Sample(k) => f.pad(&format!("sample{}", k)),
+        }
+    }
+}
+
+/// Error reading or parsing an input. Parse errors point to the line and column
+/// (both 1-based) where the input stopped making sense.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputError {
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub col: Option<usize>,
+    pub message: String,
+}
+
+impl InputError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            file: None,
+            line: None,
+            col: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at_line(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            ..Self::new(message)
+        }
+    }
+
+    pub fn at(line: usize, col: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            col: Some(col),
+            ..Self::new(message)
+        }
+    }
+
+    /// Points the error to a line, unless it already knows where it happened.
+    pub fn on_line(self, line: usize) -> Self {
+        Self {
+            line: self.line.or(Some(line)),
+            ..self
+        }
+    }
+
+    /// Names the file the error comes from, parsers only see the contents.
+    pub fn in_file(self, path: &str) -> Self {
+        let file = Path::new(path)
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or(path.to_string());
+        Self {
+            file: Some(file),
+            ..self
+        }
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}", file)?;
+            if self.line.is_some() {
+                write!(f, " ")?;
+            }
+        }
+        match (self.line, self.col) {
+            (Some(line), Some(col)) => write!(f, "line {}, column {}", line, col)?,
+            (Some(line), None) => write!(f, "line {}", line)?,
+            (None, Some(col)) => write!(f, "column {}", col)?,
+            _ => {}
+        }
+        if self.file.is_some() || self.line.is_some() || self.col.is_some() {
+            write!(f, ": ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for InputError {}
+
+pub fn read_input(path: &str) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|e| InputError::new(e.to_string()).in_file(path))
+}
+
+#[allow(dead_code)]
+pub fn read_lines(path: &str) -> Result<Vec<String>, InputError> {
+    Ok(read_input(path)?.lines().map(|l| l.to_string()).collect())
+}
+
+pub fn input_path(day: u8, kind: InputKind) -> String {
+    match kind {
+        InputKind::Real => format!("{}/d{}.txt", PROBLEMS_DIR, day),
+        InputKind::Sample(k) => format!("{}/d{}_sample{}.txt", PROBLEMS_DIR, day, k),
+    }
+}
+
+fn sample_number(day: u8, file_name: &str) -> Option<u8> {
+    file_name
+        .strip_prefix(&format!("d{}_sample", day))?
+        .strip_suffix(".txt")?
+        .parse()
+        .ok()
+}
+
+/// Numbers of the samples available for the day, in order.
+pub fn samples(day: u8) -> Vec<u8> {
+    let mut samples = fs::read_dir(PROBLEMS_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| sample_number(day, e.file_name().to_str()?))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    samples.sort();
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        input_path, read_input, read_lines, sample_number, samples, InputError, InputKind,
+    };
+
+    #[test]
+    fn test_input_path() {
+        assert_eq!(input_path(17, InputKind::Real), "./problems/d17.txt");
+        assert_eq!(
+            input_path(8, InputKind::Sample(3)),
+            "./problems/d8_sample3.txt"
+        );
+    }
+
+    #[test]
+    fn test_sample_number() {
+        assert_eq!(sample_number(1, "d1_sample2.txt"), Some(2));
+        assert_eq!(sample_number(1, "d10_sample1.txt"), None);
+        assert_eq!(sample_number(10, "d10_sample1.txt"), Some(1));
+        assert_eq!(sample_number(5, "d5_test.txt"), None);
+        assert_eq!(sample_number(5, "d5.txt"), None);
+    }
+
+    #[test]
+    fn test_samples() {
+        assert_eq!(samples(8), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_read() {
+        assert_eq!(
+            read_lines("./problems/d6_sample1.txt").unwrap(),
+            vec!["Time:      7  15   30", "Distance:  9  40  200"]
+        );
+
+        let err = read_input("./problems/d99.txt").unwrap_err();
+        assert_eq!(err.file, Some("d99.txt".to_string()));
+        assert_eq!(err.line, None);
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            InputError::at_line(412, "expected `}`")
+                .in_file("./problems/d19.txt")
+                .to_string(),
+            "d19.txt line 412: expected `}`"
+        );
+        assert_eq!(
+            InputError::at(3, 7, "bad digit").to_string(),
+            "line 3, column 7: bad digit"
+        );
+        assert_eq!(
+            InputError::new("empty input").in_file("d1.txt").to_string(),
+            "d1.txt: empty input"
+        );
+    }
+}
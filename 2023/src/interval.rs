@@ -0,0 +1,281 @@
+//! Half-open intervals of integers, and sets of them, for the puzzles that
+//! work on whole ranges of values at once.
+
+#![allow(dead_code)]
+
+use std::ops::Range;
+
+use num_traits::PrimInt;
+
+/// The integers from `start` up to, but not including, `end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        assert!(start <= end, "interval ends before it starts");
+        Self { start, end }
+    }
+
+    pub fn with_len(start: T, len: T) -> Self {
+        Self::new(start, start + len)
+    }
+
+    pub fn len(&self) -> T {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if !self.overlaps(other) {
+            return None;
+        }
+        Some(Self::new(
+            self.start.max(other.start),
+            self.end.min(other.end),
+        ))
+    }
+
+    /// The parts of this interval below and above `other`.
+    pub fn difference(&self, other: &Self) -> (Option<Self>, Option<Self>) {
+        let below = Self::new(self.start, self.end.min(other.start).max(self.start));
+        let above = Self::new(self.start.max(other.end).min(self.end), self.end);
+        (non_empty(below), non_empty(above))
+    }
+
+    /// The parts of this interval below `at`, and from `at` on.
+    pub fn split_at(&self, at: T) -> (Option<Self>, Option<Self>) {
+        let at = at.max(self.start).min(self.end);
+        (
+            non_empty(Self::new(self.start, at)),
+            non_empty(Self::new(at, self.end)),
+        )
+    }
+
+    /// Both intervals as a single one, if they overlap or touch.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        if self.start > other.end || other.start > self.end {
+            return None;
+        }
+        Some(Self::new(
+            self.start.min(other.start),
+            self.end.max(other.end),
+        ))
+    }
+}
+
+fn non_empty<T: PrimInt>(interval: Interval<T>) -> Option<Interval<T>> {
+    Some(interval).filter(|i| !i.is_empty())
+}
+
+impl<T: PrimInt> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+impl<T> From<Interval<T>> for Range<T> {
+    fn from(interval: Interval<T>) -> Self {
+        interval.start..interval.end
+    }
+}
+
+/// Any number of intervals. They are kept sorted, without empty ones, and
+/// with the ones that overlap or touch merged together, so two sets with the
+/// same values are always equal.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        self.intervals.push(interval);
+        self.normalise();
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// How many values are in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |acc, interval| acc + interval.len())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    /// The largest value in the set, not the end of the last interval.
+    pub fn max(&self) -> Option<T> {
+        self.intervals
+            .last()
+            .map(|interval| interval.end - T::one())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.intervals
+            .iter()
+            .any(|interval| interval.contains(value))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.iter()
+            .flat_map(|a| other.iter().filter_map(|b| a.intersection(b)))
+            .collect()
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut res = Vec::new();
+        for a in &self.intervals {
+            let mut rest = Some(*a);
+            for b in &other.intervals {
+                let Some(r) = rest else { break };
+                let (below, above) = r.difference(b);
+                res.extend(below);
+                rest = above;
+            }
+            res.extend(rest);
+        }
+        res.into_iter().collect()
+    }
+
+    /// The values below `at`, and from `at` on.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let (below, above): (Vec<_>, Vec<_>) = self.iter().map(|i| i.split_at(at)).unzip();
+        (
+            below.into_iter().flatten().collect(),
+            above.into_iter().flatten().collect(),
+        )
+    }
+
+    fn normalise(&mut self) {
+        self.intervals.retain(|i| !i.is_empty());
+        self.intervals.sort();
+
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(self.intervals.len());
+        for interval in self.intervals.drain(..) {
+            match merged.last_mut().and_then(|last| last.union(&interval)) {
+                Some(union) => *merged.last_mut().unwrap() = union,
+                None => merged.push(interval),
+            }
+        }
+        self.intervals = merged;
+    }
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self {
+            intervals: iter.into_iter().collect(),
+        };
+        set.normalise();
+        set
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        [interval].into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Interval, IntervalSet};
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let r1 = Interval::new(5, 15);
+        let r2 = Interval::new(0, 10);
+
+        assert_eq!(r1.intersection(&r2), Some(Interval::new(5, 10)));
+        assert_eq!(r1.intersection(&Interval::new(15, 20)), None);
+        assert_eq!(r1.difference(&r2), (None, Some(Interval::new(10, 15))));
+        assert_eq!(r2.difference(&r1), (Some(Interval::new(0, 5)), None));
+        assert_eq!(
+            r2.difference(&Interval::new(3, 4)),
+            (Some(Interval::new(0, 3)), Some(Interval::new(4, 10)))
+        );
+        assert_eq!(r2.difference(&Interval::new(-5, 20)), (None, None));
+        assert_eq!(r1.split_at(7), (Some((5..7).into()), Some((7..15).into())));
+        assert_eq!(r1.split_at(2), (None, Some(r1)));
+        assert_eq!(r1.union(&Interval::new(15, 20)), Some((5..20).into()));
+        assert_eq!(r1.union(&Interval::new(16, 20)), None);
+        assert!(r1.contains(5) && !r1.contains(15));
+        assert_eq!(Interval::with_len(10usize, 4).len(), 4);
+    }
+
+    #[test]
+    fn test_set() {
+        let a = set(&[(10, 20), (0, 5), (5, 7), (30, 30)]);
+        assert_eq!(a.intervals(), set(&[(0, 7), (10, 20)]).intervals());
+        assert_eq!(a.len(), 17);
+        assert_eq!((a.min(), a.max()), (Some(0), Some(19)));
+
+        let b = set(&[(3, 12), (18, 25)]);
+        assert_eq!(a.union(&b), set(&[(0, 25)]));
+        assert_eq!(a.intersection(&b), set(&[(3, 7), (10, 12), (18, 20)]));
+        assert_eq!(a.difference(&b), set(&[(0, 3), (12, 18)]));
+        assert_eq!(b.difference(&a), set(&[(7, 10), (20, 25)]));
+        assert_eq!(a.split_at(12), (set(&[(0, 7), (10, 12)]), set(&[(12, 20)])));
+        assert!(a.contains(6) && !a.contains(7));
+
+        let mut c = IntervalSet::new();
+        c.insert(Interval::new(1i64, 3));
+        c.insert(Interval::new(3, 4));
+        assert_eq!(c.intervals(), [Interval::new(1, 4)]);
+    }
+}
@@ -0,0 +1,269 @@
+//! Exact fractions and a linear system solver over them, for when `f64` can't
+//! hold the puzzle's numbers without rounding.
+
+#![allow(dead_code)]
+
+use std::{
+    error::Error,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use num_integer::Integer;
+
+/// `num / den`, always reduced and with a positive denominator, so equal
+/// fractions have equal fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "fraction with a zero denominator");
+        let g = num.gcd(&den) * den.signum();
+        Self {
+            num: num / g,
+            den: den / g,
+        }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    /// The value, if it's a whole number.
+    pub fn to_integer(self) -> Option<i128> {
+        (self.den == 1).then_some(self.num)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let g = self.den.gcd(&other.den);
+        let num = self
+            .num
+            .checked_mul(other.den / g)?
+            .checked_add(other.num.checked_mul(self.den / g)?)?;
+        Some(Self::new(num, self.den.checked_mul(other.den / g)?))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(-other)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        // Cross-reducing first keeps the products as small as they can be
+        let g1 = self.num.gcd(&other.den);
+        let g2 = other.num.gcd(&self.den);
+        let num = (self.num / g1).checked_mul(other.num / g2)?;
+        let den = (self.den / g2).checked_mul(other.den / g1)?;
+        Some(Self::new(num, den))
+    }
+
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        self.checked_mul(Self::new(other.den, other.num))
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Self { num: n, den: 1 }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Self::from(n as i128)
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.to_integer() {
+            Some(n) => write!(f, "{}", n),
+            None => write!(f, "{}/{}", self.num, self.den),
+        }
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).expect("fraction overflow")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs).expect("fraction overflow")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs).expect("fraction overflow")
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(!rhs.is_zero(), "division of a fraction by zero");
+        self.checked_div(rhs).expect("fraction overflow")
+    }
+}
+
+/// Why `solve` has no answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveError {
+    /// The equations don't have exactly one solution.
+    Singular,
+    /// Some fraction along the way doesn't fit in an `i128`.
+    Overflow,
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::Singular => write!(f, "the system has no single solution"),
+            SolveError::Overflow => write!(f, "the system's numbers are too large"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+/// The `x` with `matrix * x == rhs`, by Gauss-Jordan elimination. `matrix` is
+/// square, one row per equation.
+pub fn solve(matrix: &[Vec<Rational>], rhs: &[Rational]) -> Result<Vec<Rational>, SolveError> {
+    let n = rhs.len();
+    assert!(
+        matrix.len() == n && matrix.iter().all(|row| row.len() == n),
+        "the matrix must be square, with a row for each value of rhs"
+    );
+
+    // Each row with its right hand side at the end
+    let mut rows: Vec<Vec<Rational>> = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, b)| row.iter().copied().chain([*b]).collect())
+        .collect();
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .find(|&r| !rows[r][col].is_zero())
+            .ok_or(SolveError::Singular)?;
+        rows.swap(col, pivot);
+
+        let p = rows[col][col];
+        for value in rows[col].iter_mut() {
+            *value = value.checked_div(p).ok_or(SolveError::Overflow)?;
+        }
+
+        let pivot_row = rows[col].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r == col || factor.is_zero() {
+                continue;
+            }
+            for (value, above) in row.iter_mut().zip(&pivot_row).skip(col) {
+                let scaled = factor.checked_mul(*above).ok_or(SolveError::Overflow)?;
+                *value = value.checked_sub(scaled).ok_or(SolveError::Overflow)?;
+            }
+        }
+    }
+
+    Ok(rows.into_iter().map(|row| row[n]).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{solve, Rational, SolveError};
+
+    fn int(n: i128) -> Rational {
+        n.into()
+    }
+
+    fn rows(values: &[&[i128]]) -> Vec<Vec<Rational>> {
+        values
+            .iter()
+            .map(|row| row.iter().map(|&v| int(v)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_rational() {
+        let half = Rational::new(2, 4);
+        assert_eq!(half, Rational::new(-1, -2));
+        assert_eq!((half.numer(), half.denom()), (1, 2));
+        assert_eq!(Rational::new(3, -6).to_string(), "-1/2");
+
+        let third = Rational::new(1, 3);
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(half - third, Rational::new(1, 6));
+        assert_eq!(half * third, Rational::new(1, 6));
+        assert_eq!(half / third, Rational::new(3, 2));
+        assert_eq!((third * int(6)).to_integer(), Some(2));
+        assert_eq!(half.to_integer(), None);
+
+        let big = Rational::from(i128::MAX / 2);
+        assert_eq!(big.checked_mul(big), None);
+        assert_eq!(half.checked_div(Rational::ZERO), None);
+    }
+
+    #[test]
+    fn test_solve() {
+        // x + y + z = 6, 2y + 5z = -4, 2x + 5y - z = 27
+        let x = solve(
+            &rows(&[&[1, 1, 1], &[0, 2, 5], &[2, 5, -1]]),
+            &[int(6), int(-4), int(27)],
+        )
+        .unwrap();
+        assert_eq!(x, [int(5), int(3), int(-2)]);
+
+        // The first pivot has to come from another row, and the answer isn't whole
+        let x = solve(&rows(&[&[0, 2], &[3, 0]]), &[int(1), int(1)]).unwrap();
+        assert_eq!(x, [Rational::new(1, 3), Rational::new(1, 2)]);
+    }
+
+    #[test]
+    fn test_singular() {
+        let singular = rows(&[&[1, 2], &[2, 4]]);
+        assert_eq!(
+            solve(&singular, &[int(1), int(2)]),
+            Err(SolveError::Singular)
+        );
+        assert_eq!(solve(&rows(&[&[0]]), &[int(0)]), Err(SolveError::Singular));
+    }
+}
@@ -1,4 +1,10 @@
+mod answers;
 mod assertions;
+mod beam;
+mod bench;
+mod bricks;
+mod circuit;
+mod cycle;
 mod day1;
 mod day10;
 mod day11;
@@ -24,9 +30,398 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+mod dot;
+mod graph;
 mod grid;
-mod read_file;
+mod input;
+mod interval;
+mod linear;
+mod math;
+mod parsing;
+mod point;
+mod polygon;
+mod range_map;
+mod search;
+mod solution;
+mod workflow;
+
+use std::{env, process};
+
+use answers::{Answers, Check, ANSWERS_PATH};
+use bench::format_duration;
+use input::{read_input, samples, InputError, InputKind};
+use solution::{Day, Part, Run};
+
+const USAGE: &str = "usage: aoc <day|all> [part] [--sample[=k]]
+       aoc bench <day|all> [runs] [--sample[=k]]
+       aoc verify [day|all] [--sample[=k]]
+       aoc record [day|all] [part] [--sample[=k]]
+       aoc dot <day> [--sample[=k]]";
+const DEFAULT_BENCH_RUNS: usize = 10;
+
+enum Command {
+    /// Runs the given day, or all of them
+    Run(Option<u8>, Vec<Part>),
+    /// Runs the given day, or all of them, that many times
+    Bench(Option<u8>, usize),
+    /// Checks the answers against the answers file
+    Verify(Option<u8>),
+    /// Saves the current answers as the expected ones, for both parts unless
+    /// one is given
+    Record(Option<u8>, Option<Part>),
+    /// Prints the day's input as a Graphviz graph
+    Dot(u8),
+}
+
+/// Inputs picked with the `--sample` flag
+#[derive(Clone, Copy)]
+enum Inputs {
+    Real,
+    AllSamples,
+    Sample(u8),
+}
+
+fn days() -> Vec<Day> {
+    vec![
+        Day::new::<day1::Day1>(1),
+        Day::new::<day2::Day2>(2),
+        Day::new::<day3::Day3>(3),
+        Day::new::<day4::Day4>(4),
+        Day::new::<day5::Day5>(5),
+        Day::new::<day6::Day6>(6),
+        Day::new::<day7::Day7>(7),
+        Day::new::<day8::Day8>(8),
+        Day::new::<day9::Day9>(9),
+        Day::new::<day10::Day10>(10),
+        Day::new::<day11::Day11>(11),
+        Day::new::<day12::Day12>(12),
+        Day::new::<day13::Day13>(13),
+        Day::new::<day14::Day14>(14),
+        Day::new::<day15::Day15>(15),
+        Day::new::<day16::Day16>(16),
+        Day::new::<day17::Day17>(17),
+        Day::new::<day18::Day18>(18),
+        Day::new::<day19::Day19>(19),
+        Day::new::<day20::Day20>(20),
+        Day::new::<day21::Day21>(21),
+        Day::new::<day22::Day22>(22),
+        Day::new::<day23::Day23>(23),
+        Day::new::<day24::Day24>(24),
+        Day::new::<day25::Day25>(25),
+    ]
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn parse_day(day: &str) -> Option<Option<u8>> {
+    if day == "all" {
+        Some(None)
+    } else {
+        Some(Some(day.parse().ok()?))
+    }
+}
+
+fn parse_part(part: &str) -> Option<Vec<Part>> {
+    Some(vec![Part::from_num(part.parse().ok()?)?])
+}
+
+fn parse_args(args: &[String]) -> Option<Command> {
+    let both = vec![Part::One, Part::Two];
+    match args {
+        [bench, day] if bench == "bench" => {
+            Some(Command::Bench(parse_day(day)?, DEFAULT_BENCH_RUNS))
+        }
+        [bench, day, runs] if bench == "bench" => Some(Command::Bench(
+            parse_day(day)?,
+            runs.parse().ok().filter(|r| *r > 0)?,
+        )),
+        [verify] if verify == "verify" => Some(Command::Verify(None)),
+        [verify, day] if verify == "verify" => Some(Command::Verify(parse_day(day)?)),
+        [record] if record == "record" => Some(Command::Record(None, None)),
+        [record, day] if record == "record" => Some(Command::Record(parse_day(day)?, None)),
+        [record, day, part] if record == "record" => Some(Command::Record(
+            parse_day(day)?,
+            Some(Part::from_num(part.parse().ok()?)?),
+        )),
+        [dot, day] if dot == "dot" => Some(Command::Dot(day.parse().ok()?)),
+        [day] => Some(Command::Run(parse_day(day)?, both)),
+        [day, part] => Some(Command::Run(parse_day(day)?, parse_part(part)?)),
+        _ => None,
+    }
+}
+
+/// Takes the `--sample` flag out of the arguments. Without a number it selects
+/// every sample of the day.
+fn parse_inputs(args: &mut Vec<String>) -> Option<Inputs> {
+    let mut inputs = Inputs::Real;
+    let mut rest = Vec::new();
+    for arg in args.drain(..) {
+        if arg == "--sample" {
+            inputs = Inputs::AllSamples;
+        } else if let Some(k) = arg.strip_prefix("--sample=") {
+            inputs = Inputs::Sample(k.parse().ok()?);
+        } else {
+            rest.push(arg);
+        }
+    }
+    *args = rest;
+    Some(inputs)
+}
+
+/// Days matching the selection along with each of their inputs.
+fn selected_inputs(
+    days: &[Day],
+    selected: Option<u8>,
+    inputs: Inputs,
+) -> Vec<(&Day, InputKind, Result<String, InputError>)> {
+    let to_run = days
+        .iter()
+        .filter(|d| selected.map(|s| s == d.day).unwrap_or(true))
+        .collect::<Vec<_>>();
+
+    if to_run.is_empty() {
+        eprintln!("day {} has no solution", selected.unwrap());
+        process::exit(1);
+    }
+
+    let mut res = Vec::new();
+    for day in to_run {
+        let kinds = match inputs {
+            Inputs::Real => vec![InputKind::Real],
+            Inputs::AllSamples => samples(day.day)
+                .into_iter()
+                .map(InputKind::Sample)
+                .collect(),
+            Inputs::Sample(k) => vec![InputKind::Sample(k)],
+        };
+
+        if kinds.is_empty() && selected.is_some() {
+            eprintln!("day {} has no samples", day.day);
+        }
+
+        for kind in kinds {
+            res.push((day, kind, read_input(&day.input_path(kind))));
+        }
+    }
+    res
+}
+
+/// Runs the day on one of its inputs, parse errors point to the input file.
+fn run_input(
+    day: &Day,
+    kind: InputKind,
+    contents: &Result<String, InputError>,
+    parts: &[Part],
+) -> Result<Run, InputError> {
+    let contents = contents.as_ref().map_err(|e| e.clone())?;
+    day.run(contents, parts)
+        .map_err(|e| e.in_file(&day.input_path(kind)))
+}
+
+/// Samples often only apply to one of the parts, so when a sample has expected
+/// answers only those parts are run.
+fn parts_to_run(answers: &Answers, day: u8, kind: InputKind, requested: &[Part]) -> Vec<Part> {
+    let expected = answers.parts(day, kind);
+    match kind {
+        InputKind::Sample(_) if !expected.is_empty() => requested
+            .iter()
+            .filter(|p| expected.contains(p))
+            .cloned()
+            .collect(),
+        _ => requested.to_vec(),
+    }
+}
+
+fn run(days: &[Day], selected: Option<u8>, inputs: Inputs, parts: &[Part]) -> bool {
+    let answers = load_answers();
+    let mut ok = true;
+
+    println!(
+        "{:>4} | {:>7} | {:>5} | {:>10} | answer",
+        "day", "input", "part", "time"
+    );
+    for (day, kind, contents) in selected_inputs(days, selected, inputs) {
+        let parts = parts_to_run(&answers, day.day, kind, parts);
+        let run = match run_input(day, kind, &contents, &parts) {
+            Ok(run) => run,
+            Err(e) => {
+                eprintln!("day {}: {}", day.day, e);
+                ok = false;
+                continue;
+            }
+        };
+        println!(
+            "{:>4} | {:>7} | {:>5} | {:>10} |",
+            day.day,
+            kind,
+            "parse",
+            format_duration(run.parse_time)
+        );
+        for (part, answer, elapsed) in run.answers {
+            println!(
+                "{:>4} | {:>7} | {:>5} | {:>10} | {}",
+                day.day,
+                kind,
+                part,
+                format_duration(elapsed),
+                answer
+            );
+        }
+    }
+    ok
+}
+
+fn bench(days: &[Day], selected: Option<u8>, inputs: Inputs, runs: usize) -> bool {
+    let answers = load_answers();
+    let mut ok = true;
+
+    println!(
+        "{:>4} | {:>7} | {:>5} | {:>10} | {:>10} | {:>10}",
+        "day", "input", "part", "min", "median", "max"
+    );
+    for (day, kind, contents) in selected_inputs(days, selected, inputs) {
+        let parts = parts_to_run(&answers, day.day, kind, &[Part::One, Part::Two]);
+        let stats = contents
+            .and_then(|contents| bench::bench(day, &contents, &parts, runs))
+            .map_err(|e| e.in_file(&day.input_path(kind)));
+        let stats = match stats {
+            Ok(stats) => stats,
+            Err(e) => {
+                eprintln!("day {}: {}", day.day, e);
+                ok = false;
+                continue;
+            }
+        };
+        for (label, stats) in stats {
+            println!(
+                "{:>4} | {:>7} | {:>5} | {:>10} | {:>10} | {:>10}",
+                day.day,
+                kind,
+                label,
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.max)
+            );
+        }
+    }
+    ok
+}
+
+fn load_answers() -> Answers {
+    Answers::load(ANSWERS_PATH).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    })
+}
+
+fn verify(days: &[Day], selected: Option<u8>, inputs: Inputs) -> bool {
+    let answers = load_answers();
+    let mut ok = true;
+
+    println!(
+        "{:>4} | {:>7} | {:>4} | {:>7} | answer",
+        "day", "input", "part", "status"
+    );
+    for (day, kind, contents) in selected_inputs(days, selected, inputs) {
+        let parts = parts_to_run(&answers, day.day, kind, &[Part::One, Part::Two]);
+        let run = match run_input(day, kind, &contents, &parts) {
+            Ok(run) => run,
+            Err(e) => {
+                eprintln!("day {}: {}", day.day, e);
+                ok = false;
+                continue;
+            }
+        };
+        for (part, answer, _) in run.answers {
+            let check = answers.check(day.day, kind, part, &answer);
+            match &check {
+                Check::Fail { expected } => {
+                    ok = false;
+                    println!(
+                        "{:>4} | {:>7} | {:>4} | {:>7} | {} (expected {})",
+                        day.day, kind, part, check, answer, expected
+                    );
+                }
+                _ => println!(
+                    "{:>4} | {:>7} | {:>4} | {:>7} | {}",
+                    day.day, kind, part, check, answer
+                ),
+            }
+        }
+    }
+
+    ok
+}
+
+fn record(days: &[Day], selected: Option<u8>, inputs: Inputs, part: Option<Part>) -> bool {
+    let mut answers = load_answers();
+    let mut ok = true;
+
+    for (day, kind, contents) in selected_inputs(days, selected, inputs) {
+        let parts = match part {
+            Some(part) => vec![part],
+            None => parts_to_run(&answers, day.day, kind, &[Part::One, Part::Two]),
+        };
+        let run = match run_input(day, kind, &contents, &parts) {
+            Ok(run) => run,
+            Err(e) => {
+                eprintln!("day {}: {}", day.day, e);
+                ok = false;
+                continue;
+            }
+        };
+        for (part, answer, _) in run.answers {
+            println!("{:>4} | {:>7} | {:>4} | {}", day.day, kind, part, answer);
+            answers.set(day.day, kind, part, answer.to_string());
+        }
+    }
+
+    answers.save(ANSWERS_PATH).unwrap_or_else(|e| {
+        eprintln!("{}: {}", ANSWERS_PATH, e);
+        process::exit(1);
+    });
+    ok
+}
+
+fn dot(days: &[Day], selected: u8, inputs: Inputs) -> bool {
+    let mut ok = true;
+
+    for (day, kind, contents) in selected_inputs(days, Some(selected), inputs) {
+        let dot = contents
+            .and_then(|contents| day.dot(&contents))
+            .map_err(|e| e.in_file(&day.input_path(kind)));
+        match dot {
+            Ok(Some(dot)) => println!("{}", dot),
+            Ok(None) => {
+                eprintln!("day {} has nothing to draw", day.day);
+                return false;
+            }
+            Err(e) => {
+                eprintln!("day {}: {}", day.day, e);
+                ok = false;
+            }
+        }
+    }
+    ok
+}
 
 fn main() {
-    println!("Hello, world!");
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    let inputs = parse_inputs(&mut args).unwrap_or_else(|| usage());
+    let days = days();
+
+    let ok = match parse_args(&args).unwrap_or_else(|| usage()) {
+        Command::Run(selected, parts) => run(&days, selected, inputs, &parts),
+        Command::Bench(selected, runs) => bench(&days, selected, inputs, runs),
+        Command::Verify(selected) => verify(&days, selected, inputs),
+        Command::Record(selected, part) => record(&days, selected, inputs, part),
+        Command::Dot(selected) => dot(&days, selected, inputs),
+    };
+
+    if !ok {
+        process::exit(1);
+    }
 }
@@ -0,0 +1,127 @@
+//! Number theory for the puzzles where several things cycle at their own pace
+//! and the answer is when they all line up.
+
+#![allow(dead_code)]
+
+use num_integer::Integer;
+
+pub fn gcd_all<T: Integer + Copy>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::zero(), |acc, v| acc.gcd(&v))
+}
+
+pub fn lcm_all<T: Integer + Copy>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::one(), |acc, v| acc.lcm(&v))
+}
+
+/// `(g, x, y)` with `g` the gcd of `a` and `b`, and `a * x + b * y == g`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a.abs(), a.signum(), 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+        (g, y, x - a.div_euclid(b) * y)
+    }
+}
+
+/// The values `x` with `x % modulus == residue` for every `(residue, modulus)`,
+/// as a single `(residue, modulus)`. The moduli don't have to be coprime, but
+/// then the congruences might contradict each other and there's no solution.
+/// Moduli that aren't positive have no solution either, and neither do moduli
+/// whose lcm doesn't fit in an `i64`.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    let (mut r, mut m): (i128, i128) = (0, 1);
+
+    for (residue, modulus) in congruences {
+        if modulus <= 0 {
+            return None;
+        }
+        let (r2, m2) = (residue as i128, modulus as i128);
+        let (g, p, _) = extended_gcd(m, m2);
+        if (r2 - r).rem_euclid(g) != 0 {
+            return None;
+        }
+
+        // r + m * k is also r2 modulo m2
+        let step = m2 / g;
+        let k = ((r2 - r) / g * p).rem_euclid(step);
+        r += m * k;
+        m = i64::try_from(m * step).ok()?.into();
+        r = r.rem_euclid(m);
+    }
+
+    Some((i64::try_from(r).ok()?, i64::try_from(m).ok()?))
+}
+
+/// Things that first happen at `start` and then every `period`: the first time
+/// they all happen together. There's none if a period is zero.
+pub fn first_common(cycles: impl IntoIterator<Item = (i64, i64)>) -> Option<i64> {
+    let cycles: Vec<_> = cycles.into_iter().collect();
+    if cycles.iter().any(|&(_, period)| period <= 0) {
+        return None;
+    }
+    let (r, m) = crt(cycles
+        .iter()
+        .map(|&(start, period)| (start % period, period)))?;
+
+    let earliest = cycles.iter().map(|&(start, _)| start).max().unwrap_or(0);
+    Some(first_from(r, m, earliest))
+}
+
+/// The first value from `from` on that is `residue` modulo `modulus`.
+pub fn first_from(residue: i64, modulus: i64, from: i64) -> i64 {
+    from + (residue - from).rem_euclid(modulus)
+}
+
+#[cfg(test)]
+mod test {
+    use num_integer::Integer;
+
+    use super::{crt, extended_gcd, first_common, first_from, gcd_all, lcm_all};
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd_all([12, 18, 30]), 6);
+        assert_eq!(lcm_all([4i64, 6, 10]), 60);
+        assert_eq!(lcm_all(Vec::<u32>::new()), 1);
+
+        for (a, b) in [(240, 46), (-7, 3), (0, 5), (17, 0)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, (a as i64).gcd(&(b as i64)) as i128);
+            assert_eq!(a * x + b * y, g, "{} {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Not coprime, but they agree on the common part
+        assert_eq!(crt([(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(2, 3), (0, 0)]), None);
+
+        let (a, b) = (2_000_000_011, 3_000_000_019);
+        assert_eq!(
+            crt([(5, a), (7, b)]),
+            Some((4_800_000_054_400_000_159, 6_000_000_071_000_000_209))
+        );
+        // Coprime, but the product is past i64::MAX
+        assert_eq!(crt([(5, a), (7, b), (1, 2)]), None);
+        assert_eq!(crt([(1, 4_000_000_007), (2, 4_000_000_009)]), None);
+    }
+
+    #[test]
+    fn test_first_common() {
+        // Cycles that start at their period are the plain lcm
+        assert_eq!(first_common([(4, 4), (6, 6)]), Some(12));
+        assert_eq!(first_common([(2, 4), (3, 5)]), Some(18));
+        // 1 is 1 mod 3, but the second one only starts at 4
+        assert_eq!(first_common([(1, 3), (4, 10)]), Some(4));
+        assert_eq!(first_common([(1, 2), (2, 4)]), None);
+        assert_eq!(first_common([(1, 2), (3, 0)]), None);
+
+        assert_eq!(first_from(2, 5, 10), 12);
+        assert_eq!(first_from(2, 5, 12), 12);
+        assert_eq!(first_from(-1, 5, 0), 4);
+    }
+}
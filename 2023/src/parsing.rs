@@ -0,0 +1,237 @@
+//! Helpers for the usual shapes of puzzle input. Line numbers in the errors are
+//! counted from the start of the text given to the helper, columns from the
+//! start of the text being parsed.
+
+use std::str::FromStr;
+
+use crate::input::InputError;
+
+/// Parses a single value, ignoring the whitespace around it.
+pub fn number<T: FromStr>(s: &str) -> Result<T, InputError> {
+    let s = s.trim();
+    s.parse()
+        .map_err(|_| InputError::new(format!("not a number: `{}`", s)))
+}
+
+/// Every integer in the text, skipping whatever is between them. A `-` right
+/// before the digits is a sign unless it follows a letter or a digit, so
+/// `x=-3` is -3 and `1-3` is 1 and 3.
+pub fn ints<T: FromStr>(s: &str) -> Result<Vec<T>, InputError> {
+    let chars = s.char_indices().collect::<Vec<_>>();
+    let mut res = Vec::new();
+
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        let signed = c == '-'
+            && chars.get(i + 1).is_some_and(|(_, n)| n.is_ascii_digit())
+            && (i == 0 || !chars[i - 1].1.is_alphanumeric());
+        if !signed && !c.is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let first = i;
+        i += 1;
+        while i < chars.len() && chars[i].1.is_ascii_digit() {
+            i += 1;
+        }
+        let end = chars.get(i).map_or(s.len(), |(e, _)| *e);
+        let n = s[start..end].parse().map_err(|_| InputError {
+            col: Some(first + 1),
+            ..InputError::new(format!("number out of range: {}", &s[start..end]))
+        })?;
+        res.push(n);
+    }
+    Ok(res)
+}
+
+/// Splits on the first `sep`, trimming both sides.
+pub fn pair<'a>(s: &'a str, sep: &str) -> Result<(&'a str, &'a str), InputError> {
+    s.split_once(sep)
+        .map(|(a, b)| (a.trim(), b.trim()))
+        .ok_or(InputError::new(format!(
+            "expected `{}` in `{}`",
+            sep,
+            s.trim()
+        )))
+}
+
+/// Comma separated values, like the `1,0,1` corners of a brick or the
+/// `19, 13, 30` position of a hailstone.
+pub fn coords<T: FromStr, const N: usize>(s: &str) -> Result<[T; N], InputError> {
+    let values = s.split(',').map(number).collect::<Result<Vec<T>, _>>()?;
+    let found = values.len();
+    values
+        .try_into()
+        .map_err(|_| InputError::new(format!("expected {} values, found {}", N, found)))
+}
+
+/// Parses every line with `f`, errors point to the line they come from.
+pub fn lines<'a, T>(
+    contents: &'a str,
+    mut f: impl FnMut(&'a str) -> Result<T, InputError>,
+) -> Result<Vec<T>, InputError> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(i, l)| f(l).map_err(|e| e.on_line(i + 1)))
+        .collect()
+}
+
+/// `key: value` lines, like `Time:      7  15   30`.
+pub fn key_values(contents: &str) -> Result<Vec<(&str, &str)>, InputError> {
+    lines(contents, |l| pair(l, ":"))
+}
+
+/// Parses every group of lines separated by blank lines with `f`. Line numbers
+/// in the errors of `f` are moved to where the block starts.
+pub fn blocks<'a, T>(
+    contents: &'a str,
+    mut f: impl FnMut(&'a str) -> Result<T, InputError>,
+) -> Result<Vec<T>, InputError> {
+    let mut res = Vec::new();
+    let mut parse = |first_line: usize, block: &'a str| {
+        f(block.trim_end_matches(['\n', '\r'])).map_err(|e| InputError {
+            line: Some(e.line.map_or(first_line, |l| first_line + l - 1)),
+            ..e
+        })
+    };
+
+    // line number and offset where the current block starts
+    let mut start = None;
+    let mut offset = 0;
+    for (i, line) in contents.split_inclusive('\n').enumerate() {
+        if line.trim().is_empty() {
+            if let Some((first_line, from)) = start.take() {
+                res.push(parse(first_line, &contents[from..offset])?);
+            }
+        } else if start.is_none() {
+            start = Some((i + 1, offset));
+        }
+        offset += line.len();
+    }
+    if let Some((first_line, from)) = start {
+        res.push(parse(first_line, &contents[from..])?);
+    }
+    Ok(res)
+}
+
+/// Rows of cells, one per character. `f` returns `None` for characters that
+/// don't belong in the grid. Trailing blank lines are ignored, rows of
+/// different lengths are an error.
+pub fn grid<T>(
+    contents: &str,
+    mut f: impl FnMut(char) -> Option<T>,
+) -> Result<Vec<Vec<T>>, InputError> {
+    let rows = lines(contents.trim_end_matches(['\n', '\r']), |l| {
+        l.chars()
+            .enumerate()
+            .map(|(i, c)| {
+                f(c).ok_or(InputError {
+                    col: Some(i + 1),
+                    ..InputError::new(format!("unexpected `{}`", c))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    let width = rows.first().map_or(0, |r| r.len());
+    if width == 0 {
+        return Err(InputError::new("empty grid"));
+    }
+    match rows.iter().position(|r| r.len() != width) {
+        Some(i) => Err(InputError::at_line(
+            i + 1,
+            format!("row has {} cells, expected {}", rows[i].len(), width),
+        )),
+        None => Ok(rows),
+    }
+}
+
+pub fn char_grid(contents: &str) -> Result<Vec<Vec<char>>, InputError> {
+    grid(contents, Some)
+}
+
+pub fn digit_grid<T: From<u8>>(contents: &str) -> Result<Vec<Vec<T>>, InputError> {
+    grid(contents, |c| c.to_digit(10).map(|d| T::from(d as u8)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blocks, char_grid, coords, digit_grid, ints, key_values, lines, number, pair};
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(number::<i32>(" 42 "), Ok(42));
+        assert_eq!(
+            number::<i32>("4x").unwrap_err().to_string(),
+            "not a number: `4x`"
+        );
+
+        assert_eq!(ints::<i64>("Game 12: 3 blue, -4 red"), Ok(vec![12, 3, -4]));
+        assert_eq!(ints::<i64>("x=-3, y=1-3"), Ok(vec![-3, 1, 3]));
+        assert_eq!(ints::<i64>("no numbers"), Ok(vec![]));
+        assert_eq!(
+            ints::<u8>("1 2 300").unwrap_err().to_string(),
+            "column 5: number out of range: 300"
+        );
+    }
+
+    #[test]
+    fn test_pairs_and_coords() {
+        assert_eq!(pair("AAA = (BBB, CCC)", "="), Ok(("AAA", "(BBB, CCC)")));
+        assert!(pair("AAA", "=").is_err());
+
+        let (start, end) = pair("1,0,1~1,2,1", "~").unwrap();
+        assert_eq!(coords::<i32, 3>(start), Ok([1, 0, 1]));
+        assert_eq!(coords::<i32, 3>(end), Ok([1, 2, 1]));
+        assert_eq!(coords::<i64, 3>("19, 13, -30"), Ok([19, 13, -30]));
+        assert_eq!(
+            coords::<i32, 3>("1,2").unwrap_err().to_string(),
+            "expected 3 values, found 2"
+        );
+    }
+
+    #[test]
+    fn test_lines_and_blocks() {
+        assert_eq!(
+            key_values("Time: 7 15\nDistance: 9 40\n"),
+            Ok(vec![("Time", "7 15"), ("Distance", "9 40")])
+        );
+        assert_eq!(
+            lines("1\n2\nx\n", number::<i32>).unwrap_err().to_string(),
+            "line 3: not a number: `x`"
+        );
+
+        let contents = "1\n2\n\n\n3\nx\n4\n";
+        assert_eq!(blocks(contents, |b| Ok(b.lines().count())), Ok(vec![2, 3]));
+        assert_eq!(
+            blocks(contents, |b| lines(b, number::<i32>))
+                .unwrap_err()
+                .to_string(),
+            "line 6: not a number: `x`"
+        );
+    }
+
+    #[test]
+    fn test_grids() {
+        assert_eq!(
+            char_grid("#.\n.#\n\n"),
+            Ok(vec![vec!['#', '.'], vec!['.', '#']])
+        );
+        assert_eq!(
+            digit_grid::<i32>("12\n34"),
+            Ok(vec![vec![1, 2], vec![3, 4]])
+        );
+        assert_eq!(
+            digit_grid::<i32>("12\n3x").unwrap_err().to_string(),
+            "line 2, column 2: unexpected `x`"
+        );
+        assert_eq!(
+            char_grid("##\n#\n").unwrap_err().to_string(),
+            "line 2: row has 1 cells, expected 2"
+        );
+        assert!(char_grid("\n").is_err());
+    }
+}
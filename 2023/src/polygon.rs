@@ -0,0 +1,195 @@
+//! Polygons with corners on integer coordinates, like the lagoon of day 18 or
+//! the tube loop of day 10. Everything is computed exactly, in integers.
+
+#![allow(dead_code)]
+
+use num_integer::gcd;
+
+use crate::point::{Direction, Point};
+
+/// A closed polygon, the last corner connecting back to the first one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<(i64, i64)>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<(i64, i64)>) -> Self {
+        Self { vertices }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = Point>) -> Self {
+        Self::new(
+            points
+                .into_iter()
+                .map(|p| (p.x as i64, p.y as i64))
+                .collect(),
+        )
+    }
+
+    /// The corners reached by moving from the origin by each of `moves`, in
+    /// order. The last move should come back to the origin.
+    pub fn from_moves(moves: impl IntoIterator<Item = (Direction, i64)>) -> Self {
+        let mut pos = (0, 0);
+        let vertices = moves
+            .into_iter()
+            .map(|(dir, len)| {
+                let offset = dir.offset();
+                pos = (pos.0 + offset.x as i64 * len, pos.1 + offset.y as i64 * len);
+                pos
+            })
+            .collect();
+        Self::new(vertices)
+    }
+
+    pub fn vertices(&self) -> &[(i64, i64)] {
+        &self.vertices
+    }
+
+    /// Each side, as its two ends.
+    pub fn edges(&self) -> impl Iterator<Item = ((i64, i64), (i64, i64))> + '_ {
+        let next = self.vertices.iter().cycle().skip(1);
+        self.vertices.iter().copied().zip(next.copied())
+    }
+
+    /// Twice the area, positive when the corners go counterclockwise with y
+    /// going up (clockwise on a grid where y goes down). Shoelace formula.
+    pub fn signed_double_area(&self) -> i128 {
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| x1 as i128 * y2 as i128 - x2 as i128 * y1 as i128)
+            .sum()
+    }
+
+    /// Twice the area, which is always a whole number.
+    pub fn double_area(&self) -> i128 {
+        self.signed_double_area().abs()
+    }
+
+    /// The area, rounded down when it's a half.
+    pub fn area(&self) -> i128 {
+        self.double_area() / 2
+    }
+
+    /// How many integer points lie on the sides. With only horizontal and
+    /// vertical sides, that's also the length of the boundary.
+    pub fn boundary_points(&self) -> i128 {
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| gcd(x2 - x1, y2 - y1) as i128)
+            .sum()
+    }
+
+    /// How many integer points lie strictly inside. Pick's theorem.
+    pub fn interior_points(&self) -> i128 {
+        (self.double_area() - self.boundary_points() + 2) / 2
+    }
+
+    /// How many integer points lie inside or on the sides, the number of cells
+    /// covered when the sides are dug out of a grid.
+    pub fn lattice_points(&self) -> i128 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    pub fn on_boundary(&self, (x, y): (i64, i64)) -> bool {
+        self.edges().any(|((x1, y1), (x2, y2))| {
+            let cross = (x2 - x1) as i128 * (y - y1) as i128 - (y2 - y1) as i128 * (x - x1) as i128;
+            cross == 0 && x1.min(x2) <= x && x <= x1.max(x2) && y1.min(y2) <= y && y <= y1.max(y2)
+        })
+    }
+
+    /// Whether `point` is inside or on the sides, by counting the sides a ray
+    /// going right from it crosses.
+    pub fn contains(&self, point: (i64, i64)) -> bool {
+        if self.on_boundary(point) {
+            return true;
+        }
+
+        let (x, y) = point;
+        let crossings = self
+            .edges()
+            .filter(|((_, y1), (_, y2))| (*y1 > y) != (*y2 > y))
+            .filter(|((x1, y1), (x2, y2))| {
+                // x < x1 + (y - y1) * (x2 - x1) / (y2 - y1), without dividing
+                let lhs = (x - x1) as i128 * (y2 - y1) as i128;
+                let rhs = (y - y1) as i128 * (x2 - x1) as i128;
+                if y2 > y1 {
+                    lhs < rhs
+                } else {
+                    lhs > rhs
+                }
+            })
+            .count();
+        crossings % 2 == 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::point::Direction::*;
+
+    use super::Polygon;
+
+    #[test]
+    fn test_square() {
+        // 0..=4 on both axes, dug out it's a 5x5 square
+        let square = Polygon::from_moves([(Right, 4), (Down, 4), (Left, 4), (Up, 4)]);
+
+        assert_eq!(square.vertices()[0], (4, 0));
+        assert_eq!(square.area(), 16);
+        assert_eq!(square.boundary_points(), 16);
+        assert_eq!(square.interior_points(), 9);
+        assert_eq!(square.lattice_points(), 25);
+        assert_eq!(
+            square.signed_double_area(),
+            -Polygon::new(square.vertices().iter().rev().copied().collect()).signed_double_area()
+        );
+
+        assert!(square.contains((2, 2)));
+        assert!(square.contains((4, 1)) && square.on_boundary((4, 1)));
+        assert!(!square.contains((5, 2)) && !square.contains((2, -1)));
+    }
+
+    #[test]
+    fn test_triangle() {
+        let triangle = Polygon::new(vec![(0, 0), (4, 0), (0, 3)]);
+
+        assert_eq!(triangle.double_area(), 12);
+        // The long side only goes through its ends
+        assert_eq!(triangle.boundary_points(), 8);
+        assert_eq!(triangle.interior_points(), 3);
+
+        assert!(triangle.contains((1, 1)));
+        assert!(!triangle.contains((3, 2)));
+        assert!(!triangle.on_boundary((2, 1)) && triangle.contains((2, 1)));
+    }
+
+    #[test]
+    fn test_day18_example() {
+        let moves = [
+            (Right, 6),
+            (Down, 5),
+            (Left, 2),
+            (Down, 2),
+            (Right, 2),
+            (Down, 2),
+            (Left, 5),
+            (Up, 2),
+            (Left, 1),
+            (Up, 2),
+            (Right, 2),
+            (Up, 3),
+            (Left, 2),
+            (Up, 2),
+        ];
+        assert_eq!(Polygon::from_moves(moves).lattice_points(), 62);
+    }
+
+    #[test]
+    fn test_exact_for_large_coordinates() {
+        // Past 2^53 an f64 shoelace can't hold the area exactly, which is how
+        // day 18 part 2 used to come out one short
+        let (w, h) = (1_000_000_007, 999_999_937);
+        let rect = Polygon::from_moves([(Right, w), (Down, h), (Left, w), (Up, h)]);
+        assert_eq!(rect.area(), 999_999_943_999_999_559);
+        assert_eq!(rect.lattice_points(), (w as i128 + 1) * (h as i128 + 1));
+    }
+}
@@ -0,0 +1,214 @@
+//! Functions that move whole ranges of values by a fixed amount, like the
+//! almanac maps of day 5. Values outside every range are left as they are.
+
+#![allow(dead_code)]
+
+use num_traits::PrimInt;
+
+use crate::interval::{Interval, IntervalSet};
+
+/// Maps the values of each source interval, in order, to the values starting
+/// at its destination. The source intervals never overlap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeMap<T> {
+    pieces: Vec<(Interval<T>, T)>,
+}
+
+impl<T: PrimInt> RangeMap<T> {
+    /// The map that leaves every value as it is.
+    pub fn new() -> Self {
+        Self { pieces: Vec::new() }
+    }
+
+    /// Sends `source` to the values starting at `dest`. Panics if `source`
+    /// overlaps one of the ranges already in the map.
+    pub fn insert(&mut self, source: Interval<T>, dest: T) {
+        assert!(
+            self.pieces.iter().all(|(s, _)| !s.overlaps(&source)),
+            "source ranges of a map can't overlap"
+        );
+        self.pieces.push((source, dest));
+        self.normalise();
+    }
+
+    /// The source intervals and where each of them starts after the map.
+    pub fn pieces(&self) -> &[(Interval<T>, T)] {
+        &self.pieces
+    }
+
+    pub fn get(&self, value: T) -> T {
+        self.pieces
+            .iter()
+            .find(|(source, _)| source.contains(value))
+            .map_or(value, |(source, dest)| *dest + (value - source.start))
+    }
+
+    /// Where every value of `set` ends up.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.iter()
+            .flat_map(|interval| self.split(interval))
+            .map(|(part, dest)| Interval::with_len(dest, part.len()))
+            .collect()
+    }
+
+    /// The map that does this one and then `next`, in a single step.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut pieces = Vec::new();
+
+        for (source, dest) in &self.pieces {
+            let image = Interval::with_len(*dest, source.len());
+            for (part, part_dest) in next.split(&image) {
+                let from = source.start + (part.start - *dest);
+                pieces.push((Interval::with_len(from, part.len()), part_dest));
+            }
+        }
+
+        // Values this map leaves alone only go through `next`
+        let covered: IntervalSet<T> = self.pieces.iter().map(|(source, _)| *source).collect();
+        for (source, dest) in &next.pieces {
+            for part in IntervalSet::from(*source).difference(&covered).iter() {
+                pieces.push((*part, *dest + (part.start - source.start)));
+            }
+        }
+
+        let mut res = Self { pieces };
+        res.normalise();
+        res
+    }
+
+    /// `interval` cut wherever a source interval starts or ends, with where
+    /// each part starts after the map.
+    fn split(&self, interval: &Interval<T>) -> Vec<(Interval<T>, T)> {
+        let mut rest = IntervalSet::from(*interval);
+        let mut parts = Vec::new();
+
+        for (source, dest) in &self.pieces {
+            if let Some(part) = interval.intersection(source) {
+                parts.push((part, *dest + (part.start - source.start)));
+                rest = rest.difference(&(*source).into());
+            }
+        }
+        parts.extend(rest.iter().map(|part| (*part, part.start)));
+        parts
+    }
+
+    /// Sorts the pieces, drops the ones that don't move their values and merges
+    /// the ones that continue each other.
+    fn normalise(&mut self) {
+        self.pieces
+            .retain(|(source, dest)| !source.is_empty() && source.start != *dest);
+        self.pieces.sort();
+
+        let mut merged: Vec<(Interval<T>, T)> = Vec::with_capacity(self.pieces.len());
+        for (source, dest) in self.pieces.drain(..) {
+            match merged.last_mut() {
+                Some((last, last_dest))
+                    if last.end == source.start && *last_dest + last.len() == dest =>
+                {
+                    last.end = source.end;
+                }
+                _ => merged.push((source, dest)),
+            }
+        }
+        self.pieces = merged;
+    }
+}
+
+impl<T: PrimInt> Default for RangeMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt> FromIterator<(Interval<T>, T)> for RangeMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Interval<T>, T)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (source, dest) in iter {
+            map.insert(source, dest);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::interval::{Interval, IntervalSet};
+
+    use super::RangeMap;
+
+    /// The seed-to-soil and soil-to-fertilizer maps of the day 5 example
+    fn example() -> (RangeMap<u64>, RangeMap<u64>) {
+        let soil = [(98, 50, 2), (50, 52, 48)];
+        let fertilizer = [(15, 0, 37), (52, 37, 2), (0, 39, 15)];
+        let to_map = |descs: &[(u64, u64, u64)]| {
+            descs
+                .iter()
+                .map(|(source, dest, len)| (Interval::with_len(*source, *len), *dest))
+                .collect::<RangeMap<u64>>()
+        };
+        (to_map(&soil), to_map(&fertilizer))
+    }
+
+    #[test]
+    fn test_get() {
+        let (soil, _) = example();
+        assert_eq!(soil.get(79), 81);
+        assert_eq!(soil.get(14), 14);
+        assert_eq!(soil.get(98), 50);
+        assert_eq!(soil.get(100), 100);
+    }
+
+    #[test]
+    fn test_map_set() {
+        let (soil, _) = example();
+        let seeds: IntervalSet<u64> = [Interval::new(45, 55), Interval::new(97, 101)]
+            .into_iter()
+            .collect();
+
+        let expected: IntervalSet<u64> = [
+            Interval::new(45, 50),
+            Interval::new(52, 57),
+            Interval::new(99, 100),
+            Interval::new(50, 52),
+            Interval::new(100, 101),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(soil.map_set(&seeds), expected);
+    }
+
+    #[test]
+    fn test_then() {
+        let (soil, fertilizer) = example();
+        let both = soil.then(&fertilizer);
+
+        for seed in 0..120 {
+            assert_eq!(
+                both.get(seed),
+                fertilizer.get(soil.get(seed)),
+                "seed {}",
+                seed
+            );
+        }
+
+        let seeds: IntervalSet<u64> = Interval::new(10, 110).into();
+        assert_eq!(
+            both.map_set(&seeds),
+            fertilizer.map_set(&soil.map_set(&seeds))
+        );
+        assert_eq!(RangeMap::new().then(&soil), soil);
+        assert_eq!(soil.then(&RangeMap::new()), soil);
+    }
+
+    #[test]
+    fn test_merge() {
+        let map: RangeMap<i32> = [(Interval::new(0, 5), 10), (Interval::new(5, 8), 15)]
+            .into_iter()
+            .collect();
+        assert_eq!(map.pieces(), [(Interval::new(0, 8), 10)]);
+
+        let back: RangeMap<i32> = [(Interval::new(10, 18), 0)].into_iter().collect();
+        // 0..8 comes back to where it started, only 10..18 still moves
+        assert_eq!(map.then(&back).pieces(), back.pieces());
+    }
+}
@@ -0,0 +1,279 @@
+//! Searches over any kind of state, from the cells of a `Grid` to the crucible
+//! of day 17 with its heading and how far it went in a straight line.
+//! `successors` lists the states one move away from a state and `cost` says
+//! what moving between two of them costs. Paths include both ends.
+
+#![allow(dead_code)]
+
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+use num_traits::Zero;
+use pathfinding::directed::{
+    astar::astar as pf_astar, bfs::bfs as pf_bfs, dijkstra, strongly_connected_components as scc,
+};
+
+/// Path with the fewest moves from `start` to a state where `success` holds.
+pub fn bfs<N, IN>(
+    start: &N,
+    successors: impl FnMut(&N) -> IN,
+    success: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    IN: IntoIterator<Item = N>,
+{
+    pf_bfs(start, successors, success)
+}
+
+/// Cheapest path from `start` to a state where `success` holds, and its cost.
+pub fn dijkstra<N, C, IN>(
+    start: &N,
+    successors: impl FnMut(&N) -> IN,
+    cost: impl FnMut(&N, &N) -> C,
+    success: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    IN: IntoIterator<Item = N>,
+{
+    dijkstra::dijkstra(start, with_costs(successors, cost), success)
+}
+
+/// Same as `dijkstra`, exploring first the states that `heuristic` says are
+/// closer to the goal. The heuristic must never overestimate the cost left.
+pub fn astar<N, C, IN>(
+    start: &N,
+    successors: impl FnMut(&N) -> IN,
+    cost: impl FnMut(&N, &N) -> C,
+    heuristic: impl FnMut(&N) -> C,
+    success: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    IN: IntoIterator<Item = N>,
+{
+    pf_astar(start, with_costs(successors, cost), heuristic, success)
+}
+
+/// Cheapest way to get to every state reachable from `start`.
+pub fn distances<N, C, IN>(
+    start: &N,
+    successors: impl FnMut(&N) -> IN,
+    cost: impl FnMut(&N, &N) -> C,
+) -> Distances<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    IN: IntoIterator<Item = N>,
+{
+    Distances {
+        start: start.clone(),
+        parents: dijkstra::dijkstra_all(start, with_costs(successors, cost)),
+    }
+}
+
+/// Fewest moves to every state reachable from `start`.
+pub fn bfs_distances<N, IN>(start: N, successors: impl FnMut(&N) -> IN) -> HashMap<N, usize>
+where
+    N: Eq + Hash + Clone,
+    IN: IntoIterator<Item = N>,
+{
+    bfs_core(start, successors, None)
+}
+
+/// Fewest moves to every state at most `limit` moves away from `start`. Works
+/// on unbounded state, like a map that repeats forever.
+pub fn bfs_within<N, IN>(
+    start: N,
+    successors: impl FnMut(&N) -> IN,
+    limit: usize,
+) -> HashMap<N, usize>
+where
+    N: Eq + Hash + Clone,
+    IN: IntoIterator<Item = N>,
+{
+    bfs_core(start, successors, Some(limit))
+}
+
+/// `nodes` split into groups of states that can all reach each other. Each
+/// group comes after every other group it can reach.
+pub fn strongly_connected_components<N, IN>(
+    nodes: &[N],
+    successors: impl FnMut(&N) -> IN,
+) -> Vec<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    IN: IntoIterator<Item = N>,
+{
+    scc::strongly_connected_components(nodes, successors)
+}
+
+fn bfs_core<N, IN>(
+    start: N,
+    mut successors: impl FnMut(&N) -> IN,
+    limit: Option<usize>,
+) -> HashMap<N, usize>
+where
+    N: Eq + Hash + Clone,
+    IN: IntoIterator<Item = N>,
+{
+    let mut dist = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, d)) = queue.pop_front() {
+        if limit.is_some_and(|limit| d >= limit) {
+            continue;
+        }
+        for next in successors(&node) {
+            if !dist.contains_key(&next) {
+                dist.insert(next.clone(), d + 1);
+                queue.push_back((next, d + 1));
+            }
+        }
+    }
+    dist
+}
+
+/// `pathfinding` wants the successors paired with their cost.
+fn with_costs<N, C, IN>(
+    mut successors: impl FnMut(&N) -> IN,
+    mut cost: impl FnMut(&N, &N) -> C,
+) -> impl FnMut(&N) -> Vec<(N, C)>
+where
+    IN: IntoIterator<Item = N>,
+{
+    move |node| {
+        successors(node)
+            .into_iter()
+            .map(|next| {
+                let c = cost(node, &next);
+                (next, c)
+            })
+            .collect()
+    }
+}
+
+/// The result of `distances`, remembering how each state was reached.
+pub struct Distances<N, C> {
+    start: N,
+    parents: HashMap<N, (N, C)>,
+}
+
+impl<N, C> Distances<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Copy,
+{
+    pub fn get(&self, node: &N) -> Option<C> {
+        if *node == self.start {
+            return Some(C::zero());
+        }
+        self.parents.get(node).map(|(_, c)| *c)
+    }
+
+    /// Cheapest path from the start to `node`, if it can be reached.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.get(node)?;
+        Some(dijkstra::build_path(node, &self.parents))
+    }
+
+    /// Every reachable state with its cost, the start included.
+    pub fn iter(&self) -> impl Iterator<Item = (&N, C)> {
+        std::iter::once((&self.start, C::zero()))
+            .chain(self.parents.iter().map(|(node, (_, c))| (node, *c)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len() + 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{grid::Grid, point::Point};
+
+    use super::{
+        astar, bfs, bfs_distances, bfs_within, dijkstra, distances, strongly_connected_components,
+    };
+
+    const MAZE: &str = "\
+S.#.
+1.#.
+9...";
+
+    #[test]
+    fn test_searches() {
+        let grid: Grid<char> = MAZE.parse().unwrap();
+        let start = Point::new(0, 0);
+        let end = Point::new(3, 0);
+
+        let open = |p: &Point| {
+            grid.neighbours_at(*p)
+                .filter(|(_, c)| **c != '#')
+                .map(|(next, _)| next)
+                .collect::<Vec<_>>()
+        };
+        // Digits cost that much to step on, everything else costs 1
+        let cost = |_: &Point, next: &Point| grid[*next].to_digit(10).unwrap_or(1);
+
+        assert_eq!(bfs(&start, open, |p| *p == end).unwrap().len(), 8);
+
+        let (path, c) = dijkstra(&start, open, cost, |p| *p == end).unwrap();
+        assert_eq!(c, 7);
+        assert_eq!(path[..3], [start, Point::new(1, 0), Point::new(1, 1)]);
+
+        let manhattan = |p: &Point| p.manhattan(end) as u32;
+        assert_eq!(
+            astar(&start, open, cost, manhattan, |p| *p == end)
+                .unwrap()
+                .1,
+            7
+        );
+
+        let all = distances(&start, open, cost);
+        assert_eq!(all.len(), 10);
+        assert_eq!(all.get(&start), Some(0));
+        assert_eq!(all.get(&Point::new(0, 2)), Some(10));
+        assert_eq!(all.get(&Point::new(2, 0)), None);
+        assert_eq!(all.path_to(&Point::new(0, 1)).unwrap().len(), 2);
+        assert_eq!(all.iter().map(|(_, c)| c).max(), Some(10));
+
+        assert_eq!(bfs_distances(start, open)[&end], 7);
+        assert_eq!(bfs_within(start, open, 2).len(), 5);
+    }
+
+    #[test]
+    fn test_unbounded() {
+        // Steps on an infinite grid, only the limit stops the search
+        let steps = |p: &Point| p.orthogonal().collect::<Vec<_>>();
+        let reached = bfs_within(Point::ORIGIN, steps, 3);
+
+        assert_eq!(reached.len(), 25);
+        assert_eq!(reached[&Point::new(-2, 1)], 3);
+    }
+
+    #[test]
+    fn test_components() {
+        // 0 and 1 loop, 1 leads to the 2-3-4 loop, which leads to 5
+        let edges = [vec![1], vec![0, 2], vec![3], vec![4], vec![2, 5], vec![]];
+        let components = strongly_connected_components(&[0, 1, 2, 3, 4, 5], |&n| edges[n].clone());
+
+        let mut sorted = components
+            .iter()
+            .map(|c| {
+                let mut c = c.clone();
+                c.sort();
+                c
+            })
+            .collect::<Vec<_>>();
+        let position = |n: usize| sorted.iter().position(|c| c.contains(&n)).unwrap();
+        assert!(position(5) < position(2) && position(2) < position(0));
+        sorted.sort();
+        assert_eq!(sorted, [vec![0, 1], vec![2, 3, 4], vec![5]]);
+    }
+}
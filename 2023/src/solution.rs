@@ -0,0 +1,226 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    dot::Dot,
+    input::{input_path, InputError, InputKind},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Num(i128),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Num(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Num(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i32, i64, i128, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// A day of the puzzle: parse the input once, then answer both parts from it.
+pub trait Solution {
+    type Input;
+
+    fn parse(contents: &str) -> Result<Self::Input, InputError>;
+    fn part1(input: &Self::Input) -> Answer;
+    fn part2(input: &Self::Input) -> Answer;
+
+    /// The input drawn as a graph, for the days where its shape is the hard
+    /// part of the puzzle.
+    fn dot(_input: &Self::Input) -> Option<Dot> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn from_num(n: u8) -> Option<Part> {
+        match n {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+/// Answers of a single run, with the time spent parsing and on each part.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub parse_time: Duration,
+    pub answers: Vec<(Part, Answer, Duration)>,
+}
+
+type RunFn = fn(&str, &[Part]) -> Result<Run, InputError>;
+type DotFn = fn(&str) -> Result<Option<Dot>, InputError>;
+
+/// Type-erased handle to a `Solution`, so all days can live in one list.
+pub struct Day {
+    pub day: u8,
+    run: RunFn,
+    dot: DotFn,
+}
+
+fn run_solution<S: Solution>(contents: &str, parts: &[Part]) -> Result<Run, InputError> {
+    let now = Instant::now();
+    let input = S::parse(contents)?;
+    let parse_time = now.elapsed();
+
+    let answers = parts
+        .iter()
+        .map(|part| {
+            let now = Instant::now();
+            let answer = match part {
+                Part::One => S::part1(&input),
+                Part::Two => S::part2(&input),
+            };
+            (*part, answer, now.elapsed())
+        })
+        .collect();
+
+    Ok(Run {
+        parse_time,
+        answers,
+    })
+}
+
+fn dot_solution<S: Solution>(contents: &str) -> Result<Option<Dot>, InputError> {
+    Ok(S::dot(&S::parse(contents)?))
+}
+
+impl Day {
+    pub fn new<S: Solution>(day: u8) -> Self {
+        Self {
+            day,
+            run: run_solution::<S>,
+            dot: dot_solution::<S>,
+        }
+    }
+
+    pub fn input_path(&self, kind: InputKind) -> String {
+        input_path(self.day, kind)
+    }
+
+    pub fn run(&self, contents: &str, parts: &[Part]) -> Result<Run, InputError> {
+        (self.run)(contents, parts)
+    }
+
+    /// `None` if the day has nothing to draw.
+    pub fn dot(&self, contents: &str) -> Result<Option<Dot>, InputError> {
+        (self.dot)(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::{InputError, InputKind};
+
+    use super::{Answer, Day, Part, Solution};
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<i64>;
+
+        fn parse(contents: &str) -> Result<Self::Input, InputError> {
+            contents
+                .lines()
+                .enumerate()
+                .map(|(i, l)| {
+                    l.parse()
+                        .map_err(|_| InputError::at_line(i + 1, format!("not a number: {}", l)))
+                })
+                .collect()
+        }
+
+        fn part1(input: &Self::Input) -> Answer {
+            input.iter().sum::<i64>().into()
+        }
+
+        fn part2(input: &Self::Input) -> Answer {
+            format!("{} lines", input.len()).into()
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let day = Day::new::<Sum>(99);
+        assert_eq!(day.input_path(InputKind::Real), "./problems/d99.txt");
+
+        let res = day.run("1\n2\n3", &[Part::One, Part::Two]).unwrap().answers;
+        assert_eq!((res[0].0, &res[0].1), (Part::One, &Answer::Num(6)));
+        assert_eq!(
+            (res[1].0, &res[1].1),
+            (Part::Two, &Answer::Text("3 lines".to_string()))
+        );
+
+        assert_eq!(day.dot("1\n2"), Ok(None));
+
+        let res = day.run("4", &[Part::Two]).unwrap().answers;
+        assert_eq!(res.len(), 1);
+        assert_eq!(
+            (res[0].0, &res[0].1),
+            (Part::Two, &Answer::Text("1 lines".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_run_parse_error() {
+        let day = Day::new::<Sum>(99);
+
+        let err = day.run("1\nx\n3", &[Part::One]).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: not a number: x");
+    }
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(format!("{}", Answer::from(42usize)), "42");
+        assert_eq!(format!("{}", Answer::from(-7i64)), "-7");
+        assert_eq!(format!("{}", Answer::from("abc")), "abc");
+    }
+}
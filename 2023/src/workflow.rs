@@ -0,0 +1,962 @@
+//! Workflows that send parts from one to the next by their ratings until
+//! they're accepted or rejected, like `px{a<2006:qkq,m>2090:A,rhg}`, and the
+//! parts themselves, like `{x=787,m=2655,a=1222,s=2876}`. The grammar is in
+//! `rules.pest`.
+
+#![allow(dead_code)]
+
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+};
+
+use num_traits::PrimInt;
+use pest::{error::LineColLocation, iterators::Pair, Parser};
+use pest_derive::Parser;
+
+use crate::{
+    input::InputError,
+    interval::{Interval, IntervalSet},
+};
+
+#[derive(Parser)]
+#[grammar = "rules.pest"]
+struct WorkflowParser;
+
+/// Where something was in the input: `start` and `end` are byte offsets, and
+/// `line` and `col` (both 1-based) where it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Span {
+    fn of(pair: &Pair<'_, Rule>) -> Self {
+        let span = pair.as_span();
+        let (line, col) = span.start_pos().line_col();
+        Self {
+            start: span.start(),
+            end: span.end(),
+            line,
+            col,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Less,
+    Greater,
+}
+
+/// Where a workflow sends a part.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Target {
+    Accept,
+    Reject,
+    Workflow(String),
+}
+
+impl Target {
+    pub fn new(name: &str) -> Self {
+        match name {
+            "A" => Target::Accept,
+            "R" => Target::Reject,
+            _ => Target::Workflow(name.to_string()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Target::Accept => "A",
+            Target::Reject => "R",
+            Target::Workflow(name) => name,
+        }
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// `a<2006`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Condition {
+    pub rating: String,
+    pub op: Op,
+    pub value: i64,
+}
+
+impl Condition {
+    pub fn holds(&self, rating: i64) -> bool {
+        match self.op {
+            Op::Less => rating < self.value,
+            Op::Greater => rating > self.value,
+        }
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match self.op {
+            Op::Less => '<',
+            Op::Greater => '>',
+        };
+        write!(f, "{}{}{}", self.rating, op, self.value)
+    }
+}
+
+/// `a<2006:qkq`: parts matching the condition go to the target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub condition: Condition,
+    pub target: Target,
+    pub span: Span,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.condition, self.target)
+    }
+}
+
+/// Steps tried in order, the first one that matches picks where the part
+/// goes, and `default` if none does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workflow {
+    pub name: String,
+    pub steps: Vec<Step>,
+    pub default: Target,
+    pub span: Span,
+}
+
+impl Workflow {
+    /// Where the part goes next. Conditions on ratings the part doesn't have
+    /// never match.
+    pub fn next(&self, part: &Part) -> &Target {
+        self.steps
+            .iter()
+            .find(|step| {
+                part.get(&step.condition.rating)
+                    .is_some_and(|rating| step.condition.holds(rating))
+            })
+            .map_or(&self.default, |step| &step.target)
+    }
+
+    /// Every target, the default last.
+    pub fn targets(&self) -> impl Iterator<Item = &Target> {
+        self.steps
+            .iter()
+            .map(|step| &step.target)
+            .chain([&self.default])
+    }
+
+    fn targets_mut(&mut self) -> impl Iterator<Item = &mut Target> {
+        self.steps
+            .iter_mut()
+            .map(|step| &mut step.target)
+            .chain([&mut self.default])
+    }
+}
+
+/// `px{a<2006:qkq,m>2090:A,rfg}`
+impl Display for Workflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{{", self.name)?;
+        for step in &self.steps {
+            write!(f, "{},", step)?;
+        }
+        write!(f, "{}}}", self.default)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part {
+    pub ratings: Vec<(String, i64)>,
+    pub span: Span,
+}
+
+impl Part {
+    pub fn get(&self, rating: &str) -> Option<i64> {
+        self.ratings
+            .iter()
+            .find(|(name, _)| name == rating)
+            .map(|(_, value)| *value)
+    }
+
+    pub fn total(&self) -> i64 {
+        self.ratings.iter().map(|(_, value)| value).sum()
+    }
+}
+
+/// `{x=787,m=2655,a=1222,s=2876}`
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ratings = self
+            .ratings
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>();
+        write!(f, "{{{}}}", ratings.join(","))
+    }
+}
+
+/// Why a set of workflows can't be run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkflowError {
+    Duplicate {
+        name: String,
+        span: Span,
+    },
+    /// A step, or the default of the workflow at `span`, goes to a workflow
+    /// that doesn't exist.
+    UnknownTarget {
+        name: String,
+        span: Span,
+    },
+    /// The workflows send parts around in a loop, in this order, so some
+    /// parts might never be accepted or rejected.
+    Cycle {
+        names: Vec<String>,
+        span: Span,
+    },
+    MissingStart,
+}
+
+impl WorkflowError {
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            WorkflowError::Duplicate { span, .. }
+            | WorkflowError::UnknownTarget { span, .. }
+            | WorkflowError::Cycle { span, .. } => Some(*span),
+            WorkflowError::MissingStart => None,
+        }
+    }
+}
+
+impl Display for WorkflowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkflowError::Duplicate { name, .. } => {
+                write!(f, "workflow `{}` is defined twice", name)
+            }
+            WorkflowError::UnknownTarget { name, .. } => {
+                write!(f, "no workflow named `{}`", name)
+            }
+            WorkflowError::Cycle { names, .. } => {
+                write!(f, "workflows loop: {} -> {}", names.join(" -> "), names[0])
+            }
+            WorkflowError::MissingStart => {
+                write!(f, "no `{}` workflow to start from", Workflows::START)
+            }
+        }
+    }
+}
+
+impl Error for WorkflowError {}
+
+impl From<WorkflowError> for InputError {
+    fn from(e: WorkflowError) -> Self {
+        match e.span() {
+            Some(span) => InputError::at(span.line, span.col, e.to_string()),
+            None => InputError::new(e.to_string()),
+        }
+    }
+}
+
+/// Workflows by name, checked so that every part ends up accepted or
+/// rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workflows {
+    workflows: Vec<Workflow>,
+    index: HashMap<String, usize>,
+}
+
+impl Workflows {
+    /// Where every part starts.
+    pub const START: &'static str = "in";
+
+    pub fn new(workflows: Vec<Workflow>) -> Result<Self, WorkflowError> {
+        let mut index = HashMap::new();
+        for (i, workflow) in workflows.iter().enumerate() {
+            if index.insert(workflow.name.clone(), i).is_some() {
+                return Err(WorkflowError::Duplicate {
+                    name: workflow.name.clone(),
+                    span: workflow.span,
+                });
+            }
+        }
+
+        for workflow in &workflows {
+            let targets = workflow
+                .steps
+                .iter()
+                .map(|step| (&step.target, step.span))
+                .chain([(&workflow.default, workflow.span)]);
+            for (target, span) in targets {
+                if let Target::Workflow(name) = target {
+                    if !index.contains_key(name) {
+                        return Err(WorkflowError::UnknownTarget {
+                            name: name.clone(),
+                            span,
+                        });
+                    }
+                }
+            }
+        }
+
+        if !index.contains_key(Self::START) {
+            return Err(WorkflowError::MissingStart);
+        }
+
+        let workflows = Self { workflows, index };
+        workflows.check_cycles()?;
+        Ok(workflows)
+    }
+
+    /// The workflows, then the parts.
+    pub fn parse(contents: &str) -> Result<(Self, Vec<Part>), InputError> {
+        let input = WorkflowParser::parse(Rule::input, contents)
+            .map_err(|e| {
+                let (line, col) = match e.line_col {
+                    LineColLocation::Pos(pos) => pos,
+                    LineColLocation::Span(start, _) => start,
+                };
+                InputError::at(line, col, e.variant.message())
+            })?
+            .next()
+            .unwrap();
+
+        let mut workflows = Vec::new();
+        let mut parts = Vec::new();
+        for pair in input.into_inner() {
+            match pair.as_rule() {
+                Rule::rule_block => workflows.push(parse_workflow(pair)?),
+                Rule::parts => parts.push(parse_part(pair)?),
+                _ => {}
+            }
+        }
+
+        Ok((Self::new(workflows)?, parts))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Workflow> {
+        self.index.get(name).map(|&i| &self.workflows[i])
+    }
+
+    /// In the order they were given.
+    pub fn iter(&self) -> impl Iterator<Item = &Workflow> {
+        self.workflows.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.workflows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.workflows.is_empty()
+    }
+
+    pub fn accepts(&self, part: &Part) -> bool {
+        let mut target = Target::new(Self::START);
+        while let Target::Workflow(name) = &target {
+            target = self.index_of(name).next(part).clone();
+        }
+        target == Target::Accept
+    }
+
+    /// How many of the combinations of ratings in `ranges` are accepted,
+    /// following every branch the ratings could take at once.
+    pub fn count_accepted(&self, ranges: &RatingRanges) -> i64 {
+        self.count_from(&Target::new(Self::START), ranges.clone())
+    }
+
+    fn count_from(&self, target: &Target, mut ranges: RatingRanges) -> i64 {
+        let workflow = match target {
+            Target::Accept => return ranges.count(),
+            Target::Reject => return 0,
+            Target::Workflow(name) => self.index_of(name),
+        };
+
+        let mut accepted = 0;
+        for step in &workflow.steps {
+            let (matching, rest) = ranges.split_on(&step.condition);
+            if !matching.is_empty() {
+                accepted += self.count_from(&step.target, matching);
+            }
+            ranges = rest;
+        }
+        accepted + self.count_from(&workflow.default, ranges)
+    }
+
+    /// Only for names checked by `new`.
+    fn index_of(&self, name: &str) -> &Workflow {
+        &self.workflows[self.index[name]]
+    }
+
+    /// Depth first search through the workflows, a workflow that is reached
+    /// again while its own targets are still being searched closes a loop.
+    fn check_cycles(&self) -> Result<(), WorkflowError> {
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        enum State {
+            New,
+            Open,
+            Done,
+        }
+
+        let mut state = vec![State::New; self.len()];
+        for root in 0..self.len() {
+            if state[root] != State::New {
+                continue;
+            }
+            state[root] = State::Open;
+            let mut stack = vec![(root, self.next_workflows(root))];
+
+            while let Some((node, targets)) = stack.last_mut() {
+                let node = *node;
+                match targets.pop() {
+                    Some(next) => match state[next] {
+                        State::New => {
+                            state[next] = State::Open;
+                            stack.push((next, self.next_workflows(next)));
+                        }
+                        State::Open => {
+                            let from = stack.iter().position(|(n, _)| *n == next).unwrap();
+                            return Err(WorkflowError::Cycle {
+                                names: stack[from..]
+                                    .iter()
+                                    .map(|(n, _)| self.workflows[*n].name.clone())
+                                    .collect(),
+                                span: self.workflows[next].span,
+                            });
+                        }
+                        State::Done => {}
+                    },
+                    None => {
+                        state[node] = State::Done;
+                        stack.pop();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn next_workflows(&self, i: usize) -> Vec<usize> {
+        let mut next = self.workflows[i]
+            .targets()
+            .filter_map(|target| match target {
+                Target::Workflow(name) => Some(self.index[name]),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        next.reverse();
+        next
+    }
+}
+
+impl Workflows {
+    /// Workflows that make the same decisions in fewer steps. Steps that can't
+    /// match once the ones before them didn't are dropped, workflows that send
+    /// everything to the same place are replaced by that place, and workflows
+    /// only reached through another one's default are merged into it. Repeats
+    /// until nothing changes, since each of these can make way for the others.
+    pub fn optimised(&self) -> Self {
+        let mut workflows = self.workflows.clone();
+        loop {
+            let before = workflows.clone();
+            workflows.iter_mut().for_each(prune_steps);
+            collapse(&mut workflows);
+            inline_single_use(&mut workflows);
+            drop_unreachable(&mut workflows);
+            if workflows == before {
+                break;
+            }
+        }
+        Self::new(workflows).expect("optimising keeps the workflows valid")
+    }
+}
+
+/// One workflow per line, in the input's syntax.
+impl Display for Workflows {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self.workflows.iter().map(|w| w.to_string());
+        write!(f, "{}", lines.collect::<Vec<_>>().join("\n"))
+    }
+}
+
+/// Drops the steps that can't match given that the ones before didn't, and
+/// steps at the end that go to the default anyway. A step that matches every
+/// value left still has to stay, as parts without that rating go past it.
+fn prune_steps(workflow: &mut Workflow) {
+    // Every i64, which needs one more than i64::MAX for the end
+    let any: IntervalSet<i128> = Interval::new(i64::MIN as i128, i64::MAX as i128 + 1).into();
+    let mut left: HashMap<String, IntervalSet<i128>> = HashMap::new();
+    let mut steps = Vec::new();
+
+    for step in std::mem::take(&mut workflow.steps) {
+        let values = left
+            .entry(step.condition.rating.clone())
+            .or_insert_with(|| any.clone());
+        let (matching, rest) = split(values, &step.condition);
+        if matching.is_empty() {
+            continue;
+        }
+        *values = rest;
+        steps.push(step);
+    }
+
+    while steps.last().is_some_and(|s| s.target == workflow.default) {
+        steps.pop();
+    }
+    workflow.steps = steps;
+}
+
+/// Replaces workflows without steps by their default wherever they're used.
+/// The start has to stay, even when it decides everything on its own.
+fn collapse(workflows: &mut Vec<Workflow>) {
+    let aliases: HashMap<String, Target> = workflows
+        .iter()
+        .filter(|w| w.steps.is_empty() && w.name != Workflows::START)
+        .map(|w| (w.name.clone(), w.default.clone()))
+        .collect();
+
+    workflows.retain(|w| !aliases.contains_key(&w.name));
+    for target in workflows.iter_mut().flat_map(|w| w.targets_mut()) {
+        // Aliases can lead to other aliases, but never back
+        while let Some(alias) = match target {
+            Target::Workflow(name) => aliases.get(name),
+            _ => None,
+        } {
+            *target = alias.clone();
+        }
+    }
+}
+
+/// A workflow that is only ever the default of another one can be appended
+/// to it, as its steps are only tried once the other one's all failed.
+fn inline_single_use(workflows: &mut Vec<Workflow>) {
+    loop {
+        let mut uses: HashMap<String, usize> = HashMap::new();
+        for target in workflows.iter().flat_map(|w| w.targets()) {
+            if let Target::Workflow(name) = target {
+                *uses.entry(name.clone()).or_default() += 1;
+            }
+        }
+
+        let single = workflows.iter().find_map(|w| match &w.default {
+            Target::Workflow(name) if uses[name] == 1 && name != Workflows::START => {
+                Some(name.clone())
+            }
+            _ => None,
+        });
+        let Some(name) = single else {
+            break;
+        };
+
+        let child = workflows.iter().position(|w| w.name == name).unwrap();
+        let child = workflows.remove(child);
+        let parent = workflows
+            .iter_mut()
+            .find(|w| w.default == Target::Workflow(name.clone()))
+            .unwrap();
+        parent.steps.extend(child.steps);
+        parent.default = child.default;
+    }
+}
+
+/// Keeps only the workflows a part can get to from the start.
+fn drop_unreachable(workflows: &mut Vec<Workflow>) {
+    let index: HashMap<&str, &Workflow> = workflows.iter().map(|w| (w.name.as_str(), w)).collect();
+    let mut reached = HashSet::from([Workflows::START]);
+    let mut todo = vec![Workflows::START];
+    while let Some(name) = todo.pop() {
+        for target in index[name].targets() {
+            if let Target::Workflow(next) = target {
+                if reached.insert(next) {
+                    todo.push(next);
+                }
+            }
+        }
+    }
+
+    let reached = reached
+        .into_iter()
+        .map(|name| name.to_string())
+        .collect::<HashSet<_>>();
+    workflows.retain(|w| reached.contains(&w.name));
+}
+
+/// The values that match `condition`, and the ones that don't.
+fn split<T: PrimInt>(
+    values: &IntervalSet<T>,
+    condition: &Condition,
+) -> (IntervalSet<T>, IntervalSet<T>) {
+    let value = T::from(condition.value).expect("ratings fit in the values");
+    match condition.op {
+        Op::Less => values.split_at(value),
+        // Nothing is above the largest value
+        Op::Greater => match value.checked_add(&T::one()) {
+            Some(above) => {
+                let (below, above) = values.split_at(above);
+                (above, below)
+            }
+            None => (IntervalSet::new(), values.clone()),
+        },
+    }
+}
+
+fn parse_workflow(pair: Pair<'_, Rule>) -> Result<Workflow, InputError> {
+    let span = Span::of(&pair);
+    let mut name = String::new();
+    let mut steps = Vec::new();
+    let mut default = Target::Reject;
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::work_flow_name => name = inner.as_str().trim().to_string(),
+            Rule::cond => steps.push(parse_step(inner)?),
+            Rule::default_label => default = Target::new(inner.as_str().trim()),
+            _ => {}
+        }
+    }
+
+    Ok(Workflow {
+        name,
+        steps,
+        default,
+        span,
+    })
+}
+
+fn parse_step(pair: Pair<'_, Rule>) -> Result<Step, InputError> {
+    let span = Span::of(&pair);
+    let mut rating = "";
+    let mut op = Op::Less;
+    let mut value = 0;
+    let mut target = Target::Reject;
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::part_name => rating = inner.as_str().trim(),
+            Rule::cond_type if inner.as_str() == ">" => op = Op::Greater,
+            Rule::num_literal => value = parse_value(&inner)?,
+            Rule::work_flow_name => target = Target::new(inner.as_str().trim()),
+            _ => {}
+        }
+    }
+
+    Ok(Step {
+        condition: Condition {
+            rating: rating.to_string(),
+            op,
+            value,
+        },
+        target,
+        span,
+    })
+}
+
+fn parse_part(pair: Pair<'_, Rule>) -> Result<Part, InputError> {
+    let span = Span::of(&pair);
+    let mut ratings = Vec::new();
+
+    for part in pair.into_inner().filter(|p| p.as_rule() == Rule::part) {
+        let mut name = "";
+        let mut value = 0;
+        for inner in part.into_inner() {
+            match inner.as_rule() {
+                Rule::part_name => name = inner.as_str().trim(),
+                Rule::num_literal => value = parse_value(&inner)?,
+                _ => {}
+            }
+        }
+        ratings.push((name.to_string(), value));
+    }
+
+    Ok(Part { ratings, span })
+}
+
+/// The grammar's numbers can have decimals, but ratings are whole.
+fn parse_value(pair: &Pair<'_, Rule>) -> Result<i64, InputError> {
+    pair.as_str().parse().map_err(|_| {
+        let span = Span::of(pair);
+        InputError::at(
+            span.line,
+            span.col,
+            format!("not a rating: `{}`", pair.as_str()),
+        )
+    })
+}
+
+/// The values each rating can still have, when following every part that
+/// could go down a branch at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RatingRanges {
+    ranges: HashMap<String, IntervalSet<i64>>,
+}
+
+impl RatingRanges {
+    /// Every rating in `names` with any value in `values`.
+    pub fn new(names: &[&str], values: Interval<i64>) -> Self {
+        Self {
+            ranges: names
+                .iter()
+                .map(|name| (name.to_string(), values.into()))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, rating: &str) -> Option<&IntervalSet<i64>> {
+        self.ranges.get(rating)
+    }
+
+    /// The ratings that match `condition`, and the ones that don't. Ratings
+    /// that aren't there never match.
+    pub fn split_on(&self, condition: &Condition) -> (Self, Self) {
+        let values = self
+            .ranges
+            .get(&condition.rating)
+            .cloned()
+            .unwrap_or_default();
+        let (matching, rest) = split(&values, condition);
+
+        let mut with_matching = self.clone();
+        with_matching
+            .ranges
+            .insert(condition.rating.clone(), matching);
+        let mut with_rest = self.clone();
+        if self.ranges.contains_key(&condition.rating) {
+            with_rest.ranges.insert(condition.rating.clone(), rest);
+        }
+        (with_matching, with_rest)
+    }
+
+    /// Number of combinations of ratings.
+    pub fn count(&self) -> i64 {
+        self.ranges.values().map(|r| r.len()).product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.values().any(|r| r.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::interval::Interval;
+
+    use super::{Condition, Op, RatingRanges, Target, Workflows};
+
+    const EXAMPLE: &str = "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}";
+
+    fn all_ratings() -> RatingRanges {
+        RatingRanges::new(&["x", "m", "a", "s"], Interval::new(1, 4001))
+    }
+
+    #[test]
+    fn test_parse() {
+        let (workflows, parts) = Workflows::parse(EXAMPLE).unwrap();
+        assert_eq!(workflows.len(), 11);
+        assert_eq!(parts.len(), 5);
+
+        let px = workflows.get("px").unwrap();
+        assert_eq!(px.steps.len(), 2);
+        assert_eq!(px.steps[0].condition.to_string(), "a<2006");
+        assert_eq!(px.steps[1].condition.op, Op::Greater);
+        assert_eq!(px.steps[1].target, Target::Accept);
+        assert_eq!(px.default, Target::Workflow("rfg".to_string()));
+        assert_eq!((px.span.line, px.span.col), (1, 1));
+        assert_eq!((px.steps[1].span.line, px.steps[1].span.col), (1, 15));
+        assert_eq!(
+            &EXAMPLE[px.steps[1].span.start..px.steps[1].span.end],
+            "m>2090:A"
+        );
+
+        assert_eq!(parts[1].get("s"), Some(496));
+        assert_eq!(parts[1].get("y"), None);
+        assert_eq!(parts[0].total(), 7540);
+        assert_eq!(parts[0].span.line, 13);
+    }
+
+    #[test]
+    fn test_run() {
+        let (workflows, parts) = Workflows::parse(EXAMPLE).unwrap();
+        let accepted = parts.iter().map(|p| workflows.accepts(p));
+        assert_eq!(
+            accepted.collect::<Vec<_>>(),
+            [true, false, true, false, true]
+        );
+        assert_eq!(workflows.count_accepted(&all_ratings()), 167409079868000);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let ranges = all_ratings();
+        let x_above_3 = Condition {
+            rating: "x".to_string(),
+            op: Op::Greater,
+            value: 3,
+        };
+        let (matching, rest) = ranges.split_on(&x_above_3);
+        let a_below_32 = Condition {
+            rating: "a".to_string(),
+            op: Op::Less,
+            value: 32,
+        };
+        let (both, rest2) = matching.split_on(&a_below_32);
+
+        assert_eq!(both.count(), 3997 * 31 * 4000 * 4000);
+        assert_eq!(both.count() + rest.count() + rest2.count(), 4000i64.pow(4));
+
+        let missing = Condition {
+            rating: "y".to_string(),
+            ..x_above_3
+        };
+        let (matching, rest) = ranges.split_on(&missing);
+        assert!(matching.is_empty());
+        assert_eq!(rest, ranges);
+    }
+
+    #[test]
+    fn test_print() {
+        let (workflows, parts) = Workflows::parse(EXAMPLE).unwrap();
+        let (rules, ratings) = EXAMPLE.split_once("\n\n").unwrap();
+        assert_eq!(workflows.to_string(), rules);
+        let printed = parts.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        assert_eq!(printed.join("\n"), ratings);
+    }
+
+    #[test]
+    fn test_optimise() {
+        let (workflows, parts) = Workflows::parse(EXAMPLE).unwrap();
+        let optimised = workflows.optimised();
+        assert_eq!(
+            optimised.to_string(),
+            "\
+px{a<2006:qkq,m>2090:A,s<537:R,x>2440:R,A}
+qkq{x<1416:A,x>2662:A,R}
+in{s<1351:px,s>2770:A,m<1801:hdj,R}
+hdj{m>838:A,a>1716:R,A}"
+        );
+        for part in &parts {
+            assert_eq!(optimised.accepts(part), workflows.accepts(part));
+        }
+        assert_eq!(
+            optimised.count_accepted(&all_ratings()),
+            workflows.count_accepted(&all_ratings())
+        );
+
+        // Printed workflows can be read back
+        let reparsed = format!("{}\n\n{{x=1}}", optimised);
+        assert_eq!(
+            Workflows::parse(&reparsed).unwrap().0.to_string(),
+            optimised.to_string()
+        );
+    }
+
+    #[test]
+    fn test_prune() {
+        // x<5 can't match under 10, and nothing is left for x<15 after x>3
+        let rules = "in{x<10:A,x<5:R,x>20:b,x>3:R,x<15:A,A}\nb{m>5:A,A}\nunused{a<1:R,A}\n\n{x=1}";
+        let (workflows, _) = Workflows::parse(rules).unwrap();
+        assert_eq!(
+            workflows.optimised().to_string(),
+            "in{x<10:A,x>20:A,x>3:R,A}"
+        );
+
+        let (workflows, _) = Workflows::parse("in{x<10:b,b}\nb{m<5:A,A}\n\n{x=1}").unwrap();
+        assert_eq!(workflows.optimised().to_string(), "in{A}");
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = Workflows::parse("in{a<1:A,nope}\n{a=1}").unwrap_err();
+        assert_eq!(err.message, "no workflow named `nope`");
+        assert_eq!(err.line, Some(1));
+
+        let err = Workflows::parse("in{a<1:A,R}\nin{a<1:R,A}\n{a=1}").unwrap_err();
+        assert_eq!(err.message, "workflow `in` is defined twice");
+        assert_eq!(err.line, Some(2));
+
+        let err = Workflows::parse("xy{a<1:A,R}\n{a=1}").unwrap_err();
+        assert_eq!(err.message, "no `in` workflow to start from");
+
+        let looping = "in{a<1:A,b}\nb{s>5:c,R}\nc{x<3:A,b}\n{a=1}";
+        let err = Workflows::parse(looping).unwrap_err();
+        assert_eq!(err.message, "workflows loop: b -> c -> b");
+        assert_eq!(err.line, Some(2));
+
+        // Two ways to the same workflow isn't a loop
+        let (workflows, _) = Workflows::parse("in{a<1:b,b}\nb{s>5:A,R}\n{a=1}").unwrap();
+        assert_eq!(workflows.len(), 2);
+    }
+
+    #[test]
+    fn test_prune_missing_rating() {
+        // x>3 matches every x that gets there, but parts without x still go
+        // on to the default
+        let rules = "in{x<10:A,x>3:R,m>1:R,A}\n\n{x=1}\n{x=20}\n{m=5}\n{m=1}";
+        let (workflows, parts) = Workflows::parse(rules).unwrap();
+        let optimised = workflows.optimised();
+        assert_eq!(optimised.to_string(), "in{x<10:A,x>3:R,m>1:R,A}");
+        let accepted = parts.iter().map(|p| optimised.accepts(p));
+        assert_eq!(accepted.collect::<Vec<_>>(), [true, false, false, true]);
+        for part in &parts {
+            assert_eq!(optimised.accepts(part), workflows.accepts(part));
+        }
+    }
+
+    #[test]
+    fn test_largest_values() {
+        let rules =
+            "in{x>9223372036854775807:R,x<9223372036854775807:R,A}\n\n{x=9223372036854775807}";
+        let (workflows, parts) = Workflows::parse(rules).unwrap();
+        assert!(workflows.accepts(&parts[0]));
+
+        let optimised = workflows.optimised();
+        assert_eq!(optimised.to_string(), "in{x<9223372036854775807:R,A}");
+        assert!(optimised.accepts(&parts[0]));
+
+        let ranges = RatingRanges::new(&["x"], Interval::new(i64::MAX - 10, i64::MAX));
+        assert_eq!(workflows.count_accepted(&ranges), 0);
+    }
+
+    #[test]
+    fn test_print_without_steps() {
+        // The grammar takes a workflow that only has a default, so printing
+        // one and reading it back gives the same workflows
+        let (workflows, _) = Workflows::parse("in{a}\na{x<5:R,A}\n\n{x=1}").unwrap();
+        let optimised = workflows.optimised();
+        assert_eq!(optimised.to_string(), "in{x<5:R,A}");
+
+        let (single, _) = Workflows::parse("in{A}\n\n{x=1}").unwrap();
+        assert_eq!(single.to_string(), "in{A}");
+        let printed = format!("{}\n\n{{x=1}}", single);
+        let (reparsed, _) = Workflows::parse(&printed).unwrap();
+        assert_eq!(reparsed, single);
+    }
+}